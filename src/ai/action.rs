@@ -1,29 +1,68 @@
-use std::io;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, OnceLock};
 
-use crate::cards::card::Face;
+use crate::ai::chart::{Chart, ChartActionStrategy};
+use crate::analysis::chart::generate;
 use crate::cards::{Card, Hand};
-use crate::player::Action;
+use crate::player::{Action, AvailableActions};
+use crate::rules::Rules;
 
 pub trait ActionStrategy {
-    fn action(&self, hand: &Hand, dealer_card: &Card) -> Action;
+    fn action(&self, hand: &Hand, dealer_card: &Card, available: &AvailableActions) -> Action;
 }
 
+/// Asks on the terminal, accepting each action's name or its shortcut key. Stands once there's
+/// no more input.
 pub struct HumanActionStrategy;
 
+/// Every action with its name and shortcut key
+const CHOICES: [(Action, &str, &str); 5] = [
+    (Action::Hit, "hit", "h"),
+    (Action::Stand, "stand", "s"),
+    (Action::Double, "double", "d"),
+    (Action::Split, "split", "p"),
+    (Action::Surrender, "surrender", "r"),
+];
+
 impl ActionStrategy for HumanActionStrategy {
-    fn action(&self, hand: &Hand, dealer_card: &Card) -> Action {
-        println!("Hand: {} ({})", hand, hand.value());
-        println!("Dealer: {:?}", dealer_card);
+    fn action(&self, hand: &Hand, dealer_card: &Card, available: &AvailableActions) -> Action {
+        let soft = if hand.is_soft() { "soft " } else { "" };
+        println!(
+            "Your hand: {hand} ({soft}{}), dealer shows {dealer_card}",
+            hand.value()
+        );
+        let allowed = |action: Action| match action {
+            Action::Double => available.double,
+            Action::Split => available.split,
+            Action::Surrender => available.surrender,
+            Action::Hit | Action::Stand => true,
+        };
+        let prompt = CHOICES
+            .iter()
+            .filter(|&&(action, _, _)| allowed(action))
+            .map(|(_, name, key)| format!("{name} ({key})"))
+            .collect::<Vec<_>>()
+            .join(", ");
 
         loop {
+            print!("{prompt}: ");
+            io::stdout().flush().ok();
             let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-            match input.trim() {
-                "hit" => return Action::Hit,
-                "stand" => return Action::Stand,
-                "split" => return Action::Split,
-                "double" => return Action::Double,
-                _ => {}
+            if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+                println!();
+                return Action::Stand;
+            }
+            let input = input.trim().to_lowercase();
+            if input.is_empty() {
+                continue;
+            }
+            match CHOICES
+                .iter()
+                .find(|(_, name, key)| input == *name || input == *key)
+            {
+                Some(&(action, _, _)) if allowed(action) => return action,
+                Some((_, name, _)) => println!("You can't {name} this hand"),
+                None => println!("Unknown action `{input}`"),
             }
         }
     }
@@ -32,15 +71,13 @@ impl ActionStrategy for HumanActionStrategy {
 pub struct SimpleActionStrategy;
 
 impl ActionStrategy for SimpleActionStrategy {
-    fn action(&self, hand: &Hand, _dealer_card: &Card) -> Action {
-        if hand.cards.len() == 2 {
-            if hand.is_pair() {
-                return Action::Split;
-            }
+    fn action(&self, hand: &Hand, _dealer_card: &Card, available: &AvailableActions) -> Action {
+        if available.split {
+            return Action::Split;
+        }
 
-            if hand.value() == 11 {
-                return Action::Double;
-            }
+        if available.double && hand.value() == 11 {
+            return Action::Double;
         }
 
         if hand.value() < 15 {
@@ -51,148 +88,47 @@ impl ActionStrategy for SimpleActionStrategy {
     }
 }
 
-pub struct OptimalActionStrategy;
-
-impl ActionStrategy for OptimalActionStrategy {
-    fn action(&self, hand: &Hand, dealer_card: &Card) -> Action {
-        let initial_cards = hand.cards.len() == 2;
-
-        if hand.is_pair() && should_split(hand.cards[0].face, dealer_card.face) {
-            return Action::Split;
-        }
-
-        if hand.is_soft() {
-            get_soft_total_action(hand.value(), initial_cards, dealer_card.face)
-        } else {
-            get_hard_total_action(hand.value(), initial_cards, dealer_card.face)
-        }
-    }
-}
-
-fn should_split(pair_face: Face, dealer_face: Face) -> bool {
-    match pair_face {
-        Face::Ace | Face::Eight => true,
-        Face::Ten | Face::King | Face::Queen | Face::Jack | Face::Five => false,
-        Face::Nine => matches!(
-            dealer_face,
-            Face::Nine
-                | Face::Eight
-                | Face::Six
-                | Face::Five
-                | Face::Four
-                | Face::Three
-                | Face::Two
-        ),
-        Face::Seven => matches!(
-            dealer_face,
-            Face::Seven | Face::Six | Face::Five | Face::Four | Face::Three | Face::Two
-        ),
-        Face::Six | Face::Three | Face::Two => matches!(
-            dealer_face,
-            Face::Six | Face::Five | Face::Four | Face::Three | Face::Two
-        ),
-        Face::Four => matches!(dealer_face, Face::Five | Face::Four),
-    }
-}
-
-fn get_soft_total_action(hand_value: u8, initial_cards: bool, dealer_face: Face) -> Action {
-    match hand_value {
-        20 => Action::Stand,
-        19 => match dealer_face {
-            Face::Six => {
-                if initial_cards {
-                    Action::Double
-                } else {
-                    Action::Stand
-                }
-            }
-            _ => Action::Stand,
-        },
-        18 => match dealer_face {
-            Face::Ace | Face::King | Face::Queen | Face::Jack | Face::Ten | Face::Nine => {
-                Action::Hit
-            }
-            Face::Eight | Face::Seven => Action::Stand,
-            _ => {
-                if initial_cards {
-                    Action::Double
-                } else {
-                    Action::Stand
-                }
-            }
-        },
-        17 => match dealer_face {
-            Face::Six | Face::Five | Face::Four | Face::Three => {
-                if initial_cards {
-                    Action::Double
-                } else {
-                    Action::Hit
-                }
-            }
-            _ => Action::Hit,
-        },
-        16 | 15 => match dealer_face {
-            Face::Six | Face::Five | Face::Four => {
-                if initial_cards {
-                    Action::Double
-                } else {
-                    Action::Hit
-                }
-            }
-            _ => Action::Hit,
-        },
-        14 | 13 => match dealer_face {
-            Face::Six | Face::Five => {
-                if initial_cards {
-                    Action::Double
-                } else {
-                    Action::Hit
+/// Plays basic strategy for the table rules, from the chart generated from the exact expected
+/// value of each play
+pub struct OptimalActionStrategy(ChartActionStrategy);
+
+impl OptimalActionStrategy {
+    /// Strategy for `rules`. Generating a chart takes a while, so each is generated once for
+    /// every strategy with the same playing rules.
+    pub fn new(rules: &Rules) -> Self {
+        static CHARTS: Mutex<Vec<(Rules, Arc<OnceLock<Chart>>)>> = Mutex::new(vec![]);
+        // Only the playing rules change the chart
+        let table = Rules::default();
+        let playing = Rules {
+            penetration: table.penetration,
+            min_bet: table.min_bet,
+            max_bet: table.max_bet,
+            seats: table.seats,
+            mid_shoe_entry: table.mid_shoe_entry,
+            burn_cards: table.burn_cards,
+            ..*rules
+        };
+
+        // Charts for different rules are generated in parallel, while strategies waiting on the
+        // same rules share a single generation
+        let chart = {
+            let mut charts = CHARTS.lock().unwrap();
+            match charts.iter().find(|(rules, _)| *rules == playing) {
+                Some((_, chart)) => chart.clone(),
+                None => {
+                    let chart = Arc::new(OnceLock::new());
+                    charts.push((playing, chart.clone()));
+                    chart
                 }
             }
-            _ => Action::Hit,
-        },
-        score => unreachable!("Not a possible soft hand score: {score}"),
+        };
+        let chart = chart.get_or_init(|| generate(&playing)).clone();
+        Self(ChartActionStrategy::new(chart, *rules))
     }
 }
 
-fn get_hard_total_action(hand_value: u8, initial_cards: bool, dealer_face: Face) -> Action {
-    match hand_value {
-        17..=20 => Action::Stand,
-        13..=16 => match dealer_face {
-            Face::Six | Face::Five | Face::Four | Face::Three | Face::Two => Action::Stand,
-            _ => Action::Hit,
-        },
-        12 => match dealer_face {
-            Face::Six | Face::Five | Face::Four => Action::Stand,
-            _ => Action::Hit,
-        },
-        11 => {
-            if initial_cards {
-                Action::Double
-            } else {
-                Action::Hit
-            }
-        }
-        10 => {
-            if initial_cards {
-                match dealer_face {
-                    Face::Ace | Face::King | Face::Queen | Face::Jack | Face::Ten => Action::Hit,
-                    _ => Action::Double,
-                }
-            } else {
-                Action::Hit
-            }
-        }
-        9 => {
-            if initial_cards {
-                match dealer_face {
-                    Face::Six | Face::Five | Face::Four | Face::Three => Action::Double,
-                    _ => Action::Hit,
-                }
-            } else {
-                Action::Hit
-            }
-        }
-        _ => Action::Hit,
+impl ActionStrategy for OptimalActionStrategy {
+    fn action(&self, hand: &Hand, dealer_card: &Card, available: &AvailableActions) -> Action {
+        self.0.action(hand, dealer_card, available)
     }
 }
@@ -1,15 +1,39 @@
+use std::io::{self, Write};
+
+use crate::HandOutcome;
+use crate::ai::count::{Counter, CountingSystem};
 use crate::cards::Card;
+use crate::player::Seating;
+use crate::rules::Rules;
 
 pub trait BettingStrategy {
-    fn bet(&self, remaining: f32) -> f32;
+    fn bet(&mut self, remaining: f32) -> f32;
     fn card_dealt(&mut self, card: &Card);
     fn shuffled(&mut self);
+    /// Called for each of the player's hands once it has been settled
+    fn result(&mut self, _outcome: &HandOutcome) {}
+
+    /// Called before each round with the player's name and balance to decide whether to play
+    /// it, playing every round unless overridden
+    fn seating(&mut self, _seated: bool, _name: &str, _remaining: f32) -> Seating {
+        Seating::Play
+    }
+
+    /// Counting system used to size bets, if any
+    fn counting_system(&self) -> Option<CountingSystem> {
+        None
+    }
+
+    /// True count the next bet is sized on, if the strategy keeps one
+    fn true_count(&self) -> Option<f32> {
+        None
+    }
 }
 
 pub struct FlatBettingStrategy;
 
 impl BettingStrategy for FlatBettingStrategy {
-    fn bet(&self, _remaining: f32) -> f32 {
+    fn bet(&mut self, _remaining: f32) -> f32 {
         10.
     }
 
@@ -17,30 +41,348 @@ impl BettingStrategy for FlatBettingStrategy {
     fn shuffled(&mut self) {}
 }
 
+/// Asks on the terminal for the bet before each round. Enter repeats the last bet, `0` sits the
+/// round out and `q` gives up the seat for good, as does running out of input.
+pub struct HumanBettingStrategy {
+    min_bet: f32,
+    max_bet: f32,
+    /// Bet for the coming round, and the default for the one after
+    next: f32,
+    left: bool,
+}
+
+impl HumanBettingStrategy {
+    /// Strategy suggesting a bet of `unit` until another is typed, for rules that have passed
+    /// [`Rules::validate`]
+    pub fn new(rules: &Rules, unit: f32) -> Self {
+        Self {
+            min_bet: rules.min_bet,
+            max_bet: rules.max_bet,
+            next: unit.clamp(rules.min_bet, rules.max_bet),
+            left: false,
+        }
+    }
+}
+
+impl BettingStrategy for HumanBettingStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        if self.next > remaining {
+            println!("Betting the ${remaining} you have left");
+        }
+        self.next.min(remaining)
+    }
+
+    fn card_dealt(&mut self, _card: &Card) {}
+    fn shuffled(&mut self) {}
+
+    fn seating(&mut self, _seated: bool, name: &str, remaining: f32) -> Seating {
+        while !self.left {
+            print!(
+                "{name}, balance ${remaining}. Bet (${}), 0 to sit out or q to leave: ",
+                self.next.min(remaining)
+            );
+            io::stdout().flush().ok();
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+                println!();
+                break;
+            }
+            match input.trim().trim_start_matches('$').to_lowercase().as_str() {
+                "" => return Seating::Play,
+                "0" => return Seating::SitOut,
+                "q" | "quit" => break,
+                amount => match amount.parse() {
+                    Ok(amount) if (self.min_bet..=self.max_bet).contains(&amount) => {
+                        self.next = amount;
+                        return Seating::Play;
+                    }
+                    _ if self.max_bet == f32::MAX => println!("Bet at least ${}", self.min_bet),
+                    _ => println!("Bet from ${} to ${}", self.min_bet, self.max_bet),
+                },
+            }
+        }
+        self.left = true;
+        Seating::Leave
+    }
+}
+
 pub struct HiLoCountingStrategy {
-    count: f32,
+    counter: Counter,
 }
 
 impl HiLoCountingStrategy {
     pub fn new() -> Self {
-        Self { count: 0.0 }
+        Self {
+            counter: Counter::new(CountingSystem::HI_LO),
+        }
+    }
+}
+
+impl Default for HiLoCountingStrategy {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl BettingStrategy for HiLoCountingStrategy {
-    fn bet(&self, remaining: f32) -> f32 {
-        (10.0 + 1.0 * self.count.max(0.0)).min(remaining)
+    fn bet(&mut self, remaining: f32) -> f32 {
+        (10.0 + 1.0 * self.counter.running_count().max(0.0)).min(remaining)
     }
 
     fn card_dealt(&mut self, card: &Card) {
-        match card.face.value() {
-            2..=6 => self.count += 1.0,
-            10..=11 => self.count -= 1.0,
-            _ => {}
+        self.counter.card_dealt(card);
+    }
+
+    fn shuffled(&mut self) {
+        self.counter.shuffled();
+    }
+
+    fn counting_system(&self) -> Option<CountingSystem> {
+        Some(CountingSystem::HI_LO)
+    }
+}
+
+/// Player's edge as a linear function of the true count
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeEstimate {
+    /// Edge at a true count of zero, i.e. the negative of the house edge
+    pub intercept: f32,
+    /// Change in edge for each point of true count
+    pub slope: f32,
+}
+
+impl Default for EdgeEstimate {
+    /// Typical Hi-Lo estimate for a six deck shoe
+    fn default() -> Self {
+        Self {
+            intercept: -0.005,
+            slope: 0.005,
+        }
+    }
+}
+
+/// Bets a fraction of the Kelly optimal bet for the player's estimated edge at the current
+/// true count, betting the table minimum without an edge.
+///
+/// Full Kelly maximises the bankroll's long term growth rate, while smaller fractions trade
+/// growth for a lower risk of ruin.
+pub struct KellyBettingStrategy {
+    counter: Counter,
+    edge: EdgeEstimate,
+    /// Fraction of the full Kelly bet, e.g. 0.5 for half Kelly
+    fraction: f32,
+    /// Bets are rounded down to a multiple of this amount
+    unit: f32,
+    decks: u8,
+    min_bet: f32,
+    max_bet: f32,
+}
+
+/// Variance of a single hand of blackjack, in squared units of the initial bet
+const HAND_VARIANCE: f32 = 1.3;
+
+impl KellyBettingStrategy {
+    pub fn new(
+        rules: &Rules,
+        system: CountingSystem,
+        edge: EdgeEstimate,
+        fraction: f32,
+        unit: f32,
+    ) -> Self {
+        Self {
+            counter: Counter::new(system),
+            edge,
+            fraction,
+            unit,
+            decks: rules.decks,
+            min_bet: rules.min_bet,
+            max_bet: rules.max_bet,
         }
     }
 
+    /// Estimated edge at the current true count
+    pub fn edge(&self) -> f32 {
+        self.edge.intercept + self.edge.slope * self.counter.true_count(self.decks)
+    }
+}
+
+impl BettingStrategy for KellyBettingStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        let kelly = (self.edge() / HAND_VARIANCE).max(0.0);
+        let bet = (remaining * kelly * self.fraction / self.unit).floor() * self.unit;
+        bet.clamp(self.min_bet, self.max_bet).min(remaining)
+    }
+
+    fn card_dealt(&mut self, card: &Card) {
+        self.counter.card_dealt(card);
+    }
+
     fn shuffled(&mut self) {
-        self.count = 0.0;
+        self.counter.shuffled();
+    }
+
+    fn counting_system(&self) -> Option<CountingSystem> {
+        Some(self.counter.system())
+    }
+
+    fn true_count(&self) -> Option<f32> {
+        Some(self.counter.true_count(self.decks))
+    }
+}
+
+/// Bets a number of units from a ramp indexed by the true count. `ramp[0]` is bet at a true
+/// count of zero or below, `ramp[1]` at one and so on, with the last step used for any higher
+/// count.
+pub struct RampBettingStrategy {
+    counter: Counter,
+    decks: u8,
+    unit: f32,
+    ramp: Vec<f32>,
+}
+
+impl RampBettingStrategy {
+    pub fn new(rules: &Rules, system: CountingSystem, unit: f32, ramp: Vec<f32>) -> Self {
+        assert!(!ramp.is_empty(), "Bet ramp can't be empty");
+        Self {
+            counter: Counter::new(system),
+            decks: rules.decks,
+            unit,
+            ramp,
+        }
+    }
+}
+
+impl BettingStrategy for RampBettingStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        let true_count = self.counter.true_count(self.decks).floor().max(0.0) as usize;
+        let units = self.ramp[true_count.min(self.ramp.len() - 1)];
+        (units * self.unit).min(remaining)
+    }
+
+    fn card_dealt(&mut self, card: &Card) {
+        self.counter.card_dealt(card);
+    }
+
+    fn shuffled(&mut self) {
+        self.counter.shuffled();
+    }
+
+    fn counting_system(&self) -> Option<CountingSystem> {
+        Some(self.counter.system())
+    }
+
+    fn true_count(&self) -> Option<f32> {
+        Some(self.counter.true_count(self.decks))
+    }
+}
+
+/// Back counts the table, only playing while the true count is favourable and betting with
+/// another strategy once seated
+pub struct WongingStrategy {
+    counter: Counter,
+    decks: u8,
+    /// True count at or above which to take a seat
+    entry: f32,
+    /// True count below which to leave the table
+    exit: f32,
+    betting: Box<dyn BettingStrategy>,
+}
+
+impl WongingStrategy {
+    pub fn new(
+        rules: &Rules,
+        system: CountingSystem,
+        entry: f32,
+        exit: f32,
+        betting: Box<dyn BettingStrategy>,
+    ) -> Self {
+        Self {
+            counter: Counter::new(system),
+            decks: rules.decks,
+            entry,
+            exit,
+            betting,
+        }
+    }
+}
+
+impl BettingStrategy for WongingStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        self.betting.bet(remaining)
+    }
+
+    fn card_dealt(&mut self, card: &Card) {
+        self.counter.card_dealt(card);
+        self.betting.card_dealt(card);
+    }
+
+    fn shuffled(&mut self) {
+        self.counter.shuffled();
+        self.betting.shuffled();
+    }
+
+    fn result(&mut self, outcome: &HandOutcome) {
+        self.betting.result(outcome);
+    }
+
+    fn seating(&mut self, seated: bool, _name: &str, _remaining: f32) -> Seating {
+        let true_count = self.counter.true_count(self.decks);
+        if seated && true_count < self.exit {
+            Seating::Leave
+        } else if seated || true_count >= self.entry {
+            Seating::Play
+        } else {
+            Seating::SitOut
+        }
+    }
+
+    fn counting_system(&self) -> Option<CountingSystem> {
+        Some(self.counter.system())
+    }
+
+    fn true_count(&self) -> Option<f32> {
+        self.betting
+            .true_count()
+            .or(Some(self.counter.true_count(self.decks)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cards::card::{Face, Suit};
+
+    use super::*;
+
+    #[test]
+    fn kelly() {
+        let rules = Rules {
+            decks: 1,
+            max_bet: 500.0,
+            ..Default::default()
+        };
+        let mut strategy = KellyBettingStrategy::new(
+            &rules,
+            CountingSystem::HI_LO,
+            EdgeEstimate::default(),
+            0.5,
+            5.0,
+        );
+        assert_eq!(strategy.bet(10_000.0), rules.min_bet);
+
+        // True count of +4 after a quarter of the deck, so a 1.5% edge
+        let five = Card {
+            suit: Suit::Heart,
+            face: Face::Five,
+        };
+        let nine = Card {
+            face: Face::Nine,
+            ..five
+        };
+        (0..13).for_each(|i| strategy.card_dealt(if i < 3 { &five } else { &nine }));
+        assert!((strategy.edge() - 0.015).abs() < 1e-6);
+        // Half of 1.5% / 1.3 of the bankroll, rounded down to a multiple of 5
+        assert_eq!(strategy.bet(10_000.0), 55.0);
+        assert_eq!(strategy.bet(100_000.0), rules.max_bet);
+        assert_eq!(strategy.bet(30.0), rules.min_bet);
     }
 }
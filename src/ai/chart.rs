@@ -0,0 +1,669 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::ai::action::ActionStrategy;
+use crate::cards::card::{Face, Suit};
+use crate::cards::{Card, Hand};
+use crate::player::{Action, AvailableActions};
+use crate::rules::Rules;
+
+/// Lowest hard total in a chart
+pub const HARD_MIN: u8 = 4;
+/// Lowest soft total in a chart
+pub const SOFT_MIN: u8 = 12;
+/// Lowest hard total that may be surrendered in a chart
+pub const SURRENDER_MIN: u8 = 12;
+
+/// A single cell of a strategy chart, using the codes printed on strategy cards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartEntry {
+    /// H
+    Hit,
+    /// S
+    Stand,
+    /// D: double if allowed, otherwise hit
+    Double,
+    /// Ds: double if allowed, otherwise stand
+    DoubleOrStand,
+    /// P
+    Split,
+    /// Ph: split if doubling after splitting is allowed, otherwise hit
+    SplitIfDas,
+    /// R: surrender if allowed, otherwise play the hand from the hard totals
+    Surrender,
+}
+
+/// Column headings for each dealer upcard in a chart file
+const COLUMNS: [&str; 10] = ["2", "3", "4", "5", "6", "7", "8", "9", "10", "A"];
+
+/// Basic strategy chart, with columns for each dealer upcard from 2 through to ace.
+///
+/// Charts can be read from and written to a text format of comma separated grids, one for
+/// each of the `[hard]`, `[soft]`, `[pairs]` and optional `[surrender]` tables:
+///
+/// ```text
+/// [hard]
+///   ,2 ,3 ,4 ,5 ,6 ,7 ,8 ,9 ,10,A
+/// 4 ,H ,H ,H ,H ,H ,H ,H ,H ,H ,H
+/// ...
+/// ```
+///
+/// Rows are labelled by total, or by the paired card for `[pairs]`. Pairs and surrender tables
+/// use `-` when the hand should instead be played by its total. Lines starting with `#` are
+/// comments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    /// Hard totals from 4 to 20
+    pub hard: [[ChartEntry; 10]; 17],
+    /// Soft totals from 12 to 20
+    pub soft: [[ChartEntry; 10]; 9],
+    /// Pairs from 2 to 10 then aces, `None` when the pair should be played as a total
+    pub pairs: [[Option<ChartEntry>; 10]; 10],
+    /// Hard totals from 12 to 17, `None` when the hand shouldn't be surrendered
+    pub surrender: [[Option<ChartEntry>; 10]; 6],
+}
+
+impl Chart {
+    /// Chart that plays the same as `strategy` when given the two card hands for each cell.
+    ///
+    /// Only meaningful for strategies that play by total rather than the cards that make it up.
+    pub fn from_strategy(strategy: &dyn ActionStrategy) -> Self {
+        let mut chart = Self {
+            hard: [[ChartEntry::Hit; 10]; 17],
+            soft: [[ChartEntry::Hit; 10]; 9],
+            pairs: [[None; 10]; 10],
+            surrender: [[None; 10]; 6],
+        };
+
+        let can_double = AvailableActions {
+            double: true,
+            split: false,
+            surrender: false,
+        };
+        let cannot_double = AvailableActions {
+            double: false,
+            ..can_double
+        };
+        let entry = |hand: &Hand, dealer: &Card| match (
+            strategy.action(hand, dealer, &can_double),
+            strategy.action(hand, dealer, &cannot_double),
+        ) {
+            (Action::Double, Action::Stand) => ChartEntry::DoubleOrStand,
+            (Action::Double, _) => ChartEntry::Double,
+            (Action::Stand, _) => ChartEntry::Stand,
+            _ => ChartEntry::Hit,
+        };
+
+        for (column, dealer) in (2..=11).map(card).enumerate() {
+            for (row, total) in (HARD_MIN..=20).enumerate() {
+                let low = total.saturating_sub(10).max(2).min(total / 2);
+                let hand = hand(&[low, total - low]);
+                chart.hard[row][column] = entry(&hand, &dealer);
+
+                if (SURRENDER_MIN..SURRENDER_MIN + 6).contains(&total) {
+                    let available = AvailableActions {
+                        surrender: true,
+                        ..can_double
+                    };
+                    chart.surrender[(total - SURRENDER_MIN) as usize][column] =
+                        (strategy.action(&hand, &dealer, &available) == Action::Surrender)
+                            .then_some(ChartEntry::Surrender);
+                }
+            }
+
+            for (row, total) in (SOFT_MIN..=20).enumerate() {
+                chart.soft[row][column] = entry(&hand(&[11, total - 11]), &dealer);
+            }
+
+            for (row, value) in (2..=11).enumerate() {
+                let available = AvailableActions {
+                    split: true,
+                    ..can_double
+                };
+                chart.pairs[row][column] =
+                    (strategy.action(&hand(&[value, value]), &dealer, &available) == Action::Split)
+                        .then_some(ChartEntry::Split);
+            }
+        }
+
+        chart
+    }
+
+    /// Load a chart from a file in the chart text format
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Ok(fs::read_to_string(path)?.parse()?)
+    }
+
+    /// Action for a hand, taking into account which actions are available
+    pub fn action(
+        &self,
+        hand: &Hand,
+        dealer_card: &Card,
+        available: &AvailableActions,
+        rules: &Rules,
+    ) -> Action {
+        let column = column(dealer_card);
+        let value = hand.value();
+
+        if available.split
+            && let Some(entry) = self.pairs[hand.cards[0].face.value() as usize - 2][column]
+            && let Some(action) = entry.resolve(available, rules)
+        {
+            return action;
+        }
+
+        if value >= 21 {
+            return Action::Stand;
+        }
+
+        if hand.is_soft() {
+            return self.soft[(value - SOFT_MIN) as usize][column]
+                .resolve(available, rules)
+                .unwrap_or(Action::Hit);
+        }
+
+        if available.surrender
+            && (SURRENDER_MIN..SURRENDER_MIN + 6).contains(&value)
+            && let Some(entry) = self.surrender[(value - SURRENDER_MIN) as usize][column]
+            && let Some(action) = entry.resolve(available, rules)
+        {
+            return action;
+        }
+
+        self.hard[(value - HARD_MIN) as usize][column]
+            .resolve(available, rules)
+            .unwrap_or(Action::Hit)
+    }
+}
+
+impl ChartEntry {
+    /// Action for this entry, or `None` if the entry's action isn't available
+    fn resolve(self, available: &AvailableActions, rules: &Rules) -> Option<Action> {
+        match self {
+            ChartEntry::Hit => Some(Action::Hit),
+            ChartEntry::Stand => Some(Action::Stand),
+            ChartEntry::Double if available.double => Some(Action::Double),
+            ChartEntry::Double => Some(Action::Hit),
+            ChartEntry::DoubleOrStand if available.double => Some(Action::Double),
+            ChartEntry::DoubleOrStand => Some(Action::Stand),
+            ChartEntry::Split => available.split.then_some(Action::Split),
+            ChartEntry::SplitIfDas if !available.split => None,
+            ChartEntry::SplitIfDas if rules.double_after_split => Some(Action::Split),
+            ChartEntry::SplitIfDas => Some(Action::Hit),
+            ChartEntry::Surrender => available.surrender.then_some(Action::Surrender),
+        }
+    }
+}
+
+impl Display for ChartEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            ChartEntry::Hit => "H",
+            ChartEntry::Stand => "S",
+            ChartEntry::Double => "D",
+            ChartEntry::DoubleOrStand => "Ds",
+            ChartEntry::Split => "P",
+            ChartEntry::SplitIfDas => "Ph",
+            ChartEntry::Surrender => "R",
+        };
+        f.pad(code)
+    }
+}
+
+impl FromStr for ChartEntry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "H" => Ok(ChartEntry::Hit),
+            "S" => Ok(ChartEntry::Stand),
+            "D" => Ok(ChartEntry::Double),
+            "Ds" => Ok(ChartEntry::DoubleOrStand),
+            "P" => Ok(ChartEntry::Split),
+            "Ph" => Ok(ChartEntry::SplitIfDas),
+            "R" => Ok(ChartEntry::Surrender),
+            code => Err(format!(
+                "unknown code `{code}`, expected one of H, S, D, Ds, P, Ph or R"
+            )),
+        }
+    }
+}
+
+impl Display for Chart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional =
+            |entry: &Option<ChartEntry>| entry.map_or("-".to_string(), |e| e.to_string());
+
+        write_table(
+            f,
+            Section::Hard,
+            self.hard.iter().map(|row| row.map(|e| e.to_string())),
+        )?;
+        writeln!(f)?;
+        write_table(
+            f,
+            Section::Soft,
+            self.soft.iter().map(|row| row.map(|e| e.to_string())),
+        )?;
+        writeln!(f)?;
+        write_table(
+            f,
+            Section::Pairs,
+            self.pairs.iter().map(|row| row.each_ref().map(optional)),
+        )?;
+        writeln!(f)?;
+        write_table(
+            f,
+            Section::Surrender,
+            self.surrender
+                .iter()
+                .map(|row| row.each_ref().map(optional)),
+        )
+    }
+}
+
+fn write_table(
+    f: &mut fmt::Formatter<'_>,
+    section: Section,
+    rows: impl Iterator<Item = [String; 10]>,
+) -> fmt::Result {
+    writeln!(f, "[{}]", section.name())?;
+    write_row(f, "", COLUMNS)?;
+    for (label, row) in section.labels().iter().zip(rows) {
+        write_row(f, label, row)?;
+    }
+    Ok(())
+}
+
+/// Writes a row with each field padded to line up, without any trailing whitespace
+fn write_row(f: &mut fmt::Formatter<'_>, label: &str, row: [impl Display; 10]) -> fmt::Result {
+    write!(f, "{label:<2}")?;
+    for (i, field) in row.iter().enumerate() {
+        if i == row.len() - 1 {
+            writeln!(f, ",{field}")?;
+        } else {
+            write!(f, ",{field:<2}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Error in a chart file, with the 1-based line and column of the offending field
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseChartError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseChartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseChartError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Hard,
+    Soft,
+    Pairs,
+    Surrender,
+}
+
+impl Section {
+    const VARIANTS: [Self; 4] = [Self::Hard, Self::Soft, Self::Pairs, Self::Surrender];
+
+    fn name(self) -> &'static str {
+        match self {
+            Section::Hard => "hard",
+            Section::Soft => "soft",
+            Section::Pairs => "pairs",
+            Section::Surrender => "surrender",
+        }
+    }
+
+    fn labels(self) -> Vec<String> {
+        match self {
+            Section::Hard => (HARD_MIN..=20).map(|t| t.to_string()).collect(),
+            Section::Soft => (SOFT_MIN..=20).map(|t| t.to_string()).collect(),
+            Section::Pairs => COLUMNS.map(String::from).to_vec(),
+            Section::Surrender => (SURRENDER_MIN..SURRENDER_MIN + 6)
+                .map(|t| t.to_string())
+                .collect(),
+        }
+    }
+
+    /// Parse a single cell, where `None` is written as `-`
+    fn parse_entry(self, field: &str) -> Result<Option<ChartEntry>, String> {
+        let entry = match field {
+            "-" if matches!(self, Section::Pairs | Section::Surrender) => return Ok(None),
+            field => field.parse()?,
+        };
+        let allowed = match self {
+            Section::Hard | Section::Soft => {
+                !matches!(entry, ChartEntry::Split | ChartEntry::SplitIfDas)
+            }
+            Section::Pairs => true,
+            Section::Surrender => entry == ChartEntry::Surrender,
+        };
+        if allowed {
+            Ok(Some(entry))
+        } else {
+            Err(format!(
+                "`{field}` isn't allowed in the [{}] table",
+                self.name()
+            ))
+        }
+    }
+}
+
+impl FromStr for Chart {
+    type Err = ParseChartError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chart = Chart {
+            hard: [[ChartEntry::Hit; 10]; 17],
+            soft: [[ChartEntry::Hit; 10]; 9],
+            pairs: [[None; 10]; 10],
+            surrender: [[None; 10]; 6],
+        };
+        let mut filled: Vec<Vec<bool>> = Section::VARIANTS
+            .iter()
+            .map(|section| vec![false; section.labels().len()])
+            .collect();
+        let mut section = None;
+        let mut header = false;
+        let error = |line: usize, column: usize, message: String| ParseChartError {
+            line,
+            column,
+            message,
+        };
+
+        for (i, line) in s.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let found = Section::VARIANTS
+                    .into_iter()
+                    .find(|s| s.name() == name.trim());
+                section = Some(
+                    found
+                        .ok_or_else(|| error(line_number, 1, format!("unknown table [{name}]")))?,
+                );
+                header = false;
+                continue;
+            }
+
+            let Some(current) = section else {
+                return Err(error(
+                    line_number,
+                    1,
+                    "expected a table heading such as [hard]".to_string(),
+                ));
+            };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() != COLUMNS.len() + 1 {
+                return Err(error(
+                    line_number,
+                    fields.len().min(COLUMNS.len() + 1),
+                    format!(
+                        "expected {} fields, found {}",
+                        COLUMNS.len() + 1,
+                        fields.len()
+                    ),
+                ));
+            }
+
+            if !header {
+                for (column, (field, expected)) in fields[1..].iter().zip(COLUMNS).enumerate() {
+                    if *field != expected {
+                        return Err(error(
+                            line_number,
+                            column + 2,
+                            format!("expected dealer card `{expected}`, found `{field}`"),
+                        ));
+                    }
+                }
+                header = true;
+                continue;
+            }
+
+            let labels = current.labels();
+            let row = labels.iter().position(|l| l == fields[0]).ok_or_else(|| {
+                error(
+                    line_number,
+                    1,
+                    format!(
+                        "unknown row `{}` in the [{}] table",
+                        fields[0],
+                        current.name()
+                    ),
+                )
+            })?;
+            let seen = &mut filled[current as usize][row];
+            if *seen {
+                return Err(error(
+                    line_number,
+                    1,
+                    format!(
+                        "duplicate row `{}` in the [{}] table",
+                        fields[0],
+                        current.name()
+                    ),
+                ));
+            }
+            *seen = true;
+
+            for (column, field) in fields[1..].iter().enumerate() {
+                let entry = current
+                    .parse_entry(field)
+                    .map_err(|message| error(line_number, column + 2, message))?;
+                match current {
+                    Section::Hard => chart.hard[row][column] = entry.expect("not optional"),
+                    Section::Soft => chart.soft[row][column] = entry.expect("not optional"),
+                    Section::Pairs => chart.pairs[row][column] = entry,
+                    Section::Surrender => chart.surrender[row][column] = entry,
+                }
+            }
+        }
+
+        for section in Section::VARIANTS {
+            let filled = &filled[section as usize];
+            // Surrender table may be left out entirely when it isn't offered
+            if section == Section::Surrender && filled.iter().all(|f| !f) {
+                continue;
+            }
+            if let Some(row) = filled.iter().position(|f| !f) {
+                return Err(error(
+                    s.lines().count(),
+                    1,
+                    format!(
+                        "missing row `{}` in the [{}] table",
+                        section.labels()[row],
+                        section.name()
+                    ),
+                ));
+            }
+        }
+
+        Ok(chart)
+    }
+}
+
+/// Chart column for a dealer upcard, with aces in the last column
+pub fn column(dealer_card: &Card) -> usize {
+    dealer_card.face.value() as usize - 2
+}
+
+/// Plays according to a strategy chart
+pub struct ChartActionStrategy {
+    chart: Chart,
+    rules: Rules,
+}
+
+impl ChartActionStrategy {
+    pub fn new(chart: Chart, rules: Rules) -> Self {
+        Self { chart, rules }
+    }
+
+    /// Load the chart from a file in the chart text format
+    pub fn load(path: impl AsRef<Path>, rules: Rules) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::new(Chart::load(path)?, rules))
+    }
+}
+
+impl ActionStrategy for ChartActionStrategy {
+    fn action(&self, hand: &Hand, dealer_card: &Card, available: &AvailableActions) -> Action {
+        self.chart.action(hand, dealer_card, available, &self.rules)
+    }
+}
+
+/// Hand of cards with blackjack values from 2 to 11
+pub(crate) fn hand(cards: &[u8]) -> Hand {
+    Hand {
+        cards: cards.iter().map(|&value| card(value)).collect(),
+    }
+}
+
+/// Card with a blackjack value from 2 to 11
+pub(crate) fn card(value: u8) -> Card {
+    let face = match value {
+        11 => Face::Ace,
+        10 => Face::Ten,
+        value => Face::VARIANTS[value as usize - 1],
+    };
+    Card {
+        suit: Suit::Spade,
+        face,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ai::action::OptimalActionStrategy;
+
+    use super::*;
+
+    /// Basic strategy for six decks where the dealer stands on soft 17 and peeks, with doubling
+    /// after splits and late surrender, as printed on strategy cards. Cards don't have a row for
+    /// a soft 12, which can only be a pair of aces.
+    const SIX_DECK_S17: &str = "\
+[hard]
+  ,2 ,3 ,4 ,5 ,6 ,7 ,8 ,9 ,10,A
+4 ,H ,H ,H ,H ,H ,H ,H ,H ,H ,H
+5 ,H ,H ,H ,H ,H ,H ,H ,H ,H ,H
+6 ,H ,H ,H ,H ,H ,H ,H ,H ,H ,H
+7 ,H ,H ,H ,H ,H ,H ,H ,H ,H ,H
+8 ,H ,H ,H ,H ,H ,H ,H ,H ,H ,H
+9 ,H ,D ,D ,D ,D ,H ,H ,H ,H ,H
+10,D ,D ,D ,D ,D ,D ,D ,D ,H ,H
+11,D ,D ,D ,D ,D ,D ,D ,D ,D ,H
+12,H ,H ,S ,S ,S ,H ,H ,H ,H ,H
+13,S ,S ,S ,S ,S ,H ,H ,H ,H ,H
+14,S ,S ,S ,S ,S ,H ,H ,H ,H ,H
+15,S ,S ,S ,S ,S ,H ,H ,H ,H ,H
+16,S ,S ,S ,S ,S ,H ,H ,H ,H ,H
+17,S ,S ,S ,S ,S ,S ,S ,S ,S ,S
+18,S ,S ,S ,S ,S ,S ,S ,S ,S ,S
+19,S ,S ,S ,S ,S ,S ,S ,S ,S ,S
+20,S ,S ,S ,S ,S ,S ,S ,S ,S ,S
+
+[soft]
+  ,2 ,3 ,4 ,5 ,6 ,7 ,8 ,9 ,10,A
+12,H ,H ,H ,H ,H ,H ,H ,H ,H ,H
+13,H ,H ,H ,D ,D ,H ,H ,H ,H ,H
+14,H ,H ,H ,D ,D ,H ,H ,H ,H ,H
+15,H ,H ,D ,D ,D ,H ,H ,H ,H ,H
+16,H ,H ,D ,D ,D ,H ,H ,H ,H ,H
+17,H ,D ,D ,D ,D ,H ,H ,H ,H ,H
+18,S ,Ds,Ds,Ds,Ds,S ,S ,H ,H ,H
+19,S ,S ,S ,S ,S ,S ,S ,S ,S ,S
+20,S ,S ,S ,S ,S ,S ,S ,S ,S ,S
+
+[pairs]
+  ,2 ,3 ,4 ,5 ,6 ,7 ,8 ,9 ,10,A
+2 ,P ,P ,P ,P ,P ,P ,- ,- ,- ,-
+3 ,P ,P ,P ,P ,P ,P ,- ,- ,- ,-
+4 ,- ,- ,- ,P ,P ,- ,- ,- ,- ,-
+5 ,- ,- ,- ,- ,- ,- ,- ,- ,- ,-
+6 ,P ,P ,P ,P ,P ,- ,- ,- ,- ,-
+7 ,P ,P ,P ,P ,P ,P ,- ,- ,- ,-
+8 ,P ,P ,P ,P ,P ,P ,P ,P ,P ,P
+9 ,P ,P ,P ,P ,P ,- ,P ,P ,- ,-
+10,- ,- ,- ,- ,- ,- ,- ,- ,- ,-
+A ,P ,P ,P ,P ,P ,P ,P ,P ,P ,P
+
+[surrender]
+  ,2 ,3 ,4 ,5 ,6 ,7 ,8 ,9 ,10,A
+12,- ,- ,- ,- ,- ,- ,- ,- ,- ,-
+13,- ,- ,- ,- ,- ,- ,- ,- ,- ,-
+14,- ,- ,- ,- ,- ,- ,- ,- ,- ,-
+15,- ,- ,- ,- ,- ,- ,- ,- ,R ,-
+16,- ,- ,- ,- ,- ,- ,- ,R ,R ,R
+17,- ,- ,- ,- ,- ,- ,- ,- ,- ,-
+";
+
+    fn six_deck() -> Rules {
+        Rules {
+            decks: 6,
+            dealer_peeks: true,
+            surrender: true,
+            ..Rules::default()
+        }
+    }
+
+    /// Chart the optimal strategy plays, as generated for the rules
+    fn optimal(rules: &Rules) -> Chart {
+        Chart::from_strategy(&OptimalActionStrategy::new(rules))
+    }
+
+    #[test]
+    fn round_trip() {
+        let chart = optimal(&six_deck());
+        assert_eq!(chart.to_string().parse::<Chart>(), Ok(chart));
+    }
+
+    #[test]
+    fn six_deck_basic_strategy() {
+        let published: Chart = SIX_DECK_S17.parse().unwrap();
+        let played = optimal(&six_deck());
+        assert_eq!(played.hard, published.hard);
+        assert_eq!(played.soft[1..], published.soft[1..]);
+        assert_eq!(played.pairs, published.pairs);
+        assert_eq!(played.surrender, published.surrender);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let chart = optimal(&six_deck()).to_string();
+
+        let invalid = chart.replacen("4 ,H ,H ,H", "4 ,H ,X ,H", 1);
+        let error = invalid.parse::<Chart>().unwrap_err();
+        assert_eq!((error.line, error.column), (3, 3));
+
+        let invalid = chart.replacen("12,H ,H", "12,P ,H", 1);
+        let error = invalid.parse::<Chart>().unwrap_err();
+        assert_eq!((error.line, error.column), (11, 2));
+
+        let invalid = chart.replacen("13,", "14,", 1);
+        let error = invalid.parse::<Chart>().unwrap_err();
+        assert_eq!((error.line, error.column), (13, 1));
+
+        let invalid = chart.replacen("[soft]", "[sfot]", 1);
+        let error = invalid.parse::<Chart>().unwrap_err();
+        assert_eq!((error.line, error.column), (21, 1));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::analysis::composition::rank;
+use crate::cards::Card;
+
+/// Card counting system, assigning a tag to each card value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountingSystem {
+    pub name: &'static str,
+    /// Tag for each card value, with aces first and ten valued cards last
+    pub tags: [f32; 10],
+}
+
+impl CountingSystem {
+    pub const HI_LO: Self = Self {
+        name: "Hi-Lo",
+        tags: [-1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, -1.0],
+    };
+
+    pub const HI_OPT_I: Self = Self {
+        name: "Hi-Opt I",
+        tags: [0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, -1.0],
+    };
+
+    pub const HI_OPT_II: Self = Self {
+        name: "Hi-Opt II",
+        tags: [0.0, 1.0, 1.0, 2.0, 2.0, 1.0, 1.0, 0.0, 0.0, -2.0],
+    };
+
+    pub const OMEGA_II: Self = Self {
+        name: "Omega II",
+        tags: [0.0, 1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 0.0, -1.0, -2.0],
+    };
+
+    pub const ZEN: Self = Self {
+        name: "Zen",
+        tags: [-1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 0.0, 0.0, -2.0],
+    };
+
+    pub const HALVES: Self = Self {
+        name: "Wong Halves",
+        tags: [-1.0, 0.5, 1.0, 1.0, 1.5, 1.0, 0.5, 0.0, -0.5, -1.0],
+    };
+
+    /// Every built in system
+    pub const SYSTEMS: [Self; 6] = [
+        Self::HI_LO,
+        Self::HI_OPT_I,
+        Self::HI_OPT_II,
+        Self::OMEGA_II,
+        Self::ZEN,
+        Self::HALVES,
+    ];
+
+    /// Built in system with the given name, ignoring case and punctuation
+    pub fn by_name(name: &str) -> Option<Self> {
+        let simplify = |s: &str| {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        };
+        Self::SYSTEMS
+            .into_iter()
+            .find(|system| simplify(system.name) == simplify(name))
+    }
+
+    pub fn tag(&self, card: &Card) -> f32 {
+        self.tags[rank(card) as usize - 1]
+    }
+}
+
+/// Keeps the running count of the cards seen since the last shuffle
+#[derive(Debug, Clone)]
+pub struct Counter {
+    system: CountingSystem,
+    running: f32,
+    seen: u32,
+}
+
+impl Counter {
+    pub fn new(system: CountingSystem) -> Self {
+        Self {
+            system,
+            running: 0.0,
+            seen: 0,
+        }
+    }
+
+    pub fn card_dealt(&mut self, card: &Card) {
+        self.running += self.system.tag(card);
+        self.seen += 1;
+    }
+
+    pub fn shuffled(&mut self) {
+        self.running = 0.0;
+        self.seen = 0;
+    }
+
+    pub fn system(&self) -> CountingSystem {
+        self.system
+    }
+
+    pub fn running_count(&self) -> f32 {
+        self.running
+    }
+
+    /// Running count per deck left to be dealt from a shoe of `decks`
+    pub fn true_count(&self, decks: u8) -> f32 {
+        let remaining = (decks as u32 * 52).saturating_sub(self.seen).max(1);
+        self.running / (remaining as f32 / 52.0)
+    }
+}
@@ -1,2 +1,6 @@
 pub mod action;
 pub mod betting;
+pub mod chart;
+pub mod count;
+pub mod progression;
+pub mod registry;
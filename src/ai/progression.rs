@@ -0,0 +1,365 @@
+//! Classic betting progressions, which raise and lower bets based on previous results.
+//!
+//! Each progression steps once per round on the net result of all of the player's hands,
+//! so the hands of a split count as a single result. None of them change the house edge.
+
+use std::num::NonZeroU32;
+
+use crate::HandOutcome;
+use crate::ai::betting::BettingStrategy;
+use crate::cards::Card;
+
+/// Accumulates hand outcomes until the next bet
+#[derive(Debug, Default)]
+struct Round {
+    net: f32,
+    played: bool,
+}
+
+impl Round {
+    fn record(&mut self, outcome: &HandOutcome) {
+        self.net += outcome.net;
+        self.played = true;
+    }
+
+    /// Net result of the round since the last bet, if one was played
+    fn take(&mut self) -> Option<f32> {
+        if !self.played {
+            return None;
+        }
+        self.played = false;
+        Some(std::mem::take(&mut self.net))
+    }
+}
+
+/// Doubles the bet after every loss, returning to one unit after a win
+pub struct MartingaleStrategy {
+    unit: f32,
+    current: f32,
+    round: Round,
+}
+
+impl MartingaleStrategy {
+    pub fn new(unit: f32) -> Self {
+        Self {
+            unit,
+            current: unit,
+            round: Round::default(),
+        }
+    }
+}
+
+impl BettingStrategy for MartingaleStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        match self.round.take() {
+            Some(net) if net < 0.0 => self.current *= 2.0,
+            Some(net) if net > 0.0 => self.current = self.unit,
+            _ => {}
+        }
+        self.current.min(remaining)
+    }
+
+    fn card_dealt(&mut self, _card: &Card) {}
+    fn shuffled(&mut self) {}
+
+    fn result(&mut self, outcome: &HandOutcome) {
+        self.round.record(outcome);
+    }
+}
+
+/// Doubles the bet after every win until `max_wins` in a row, returning to one unit after a
+/// loss or a completed streak
+pub struct ParoliStrategy {
+    unit: f32,
+    max_wins: NonZeroU32,
+    wins: u32,
+    round: Round,
+}
+
+impl ParoliStrategy {
+    pub fn new(unit: f32, max_wins: NonZeroU32) -> Self {
+        Self {
+            unit,
+            max_wins,
+            wins: 0,
+            round: Round::default(),
+        }
+    }
+}
+
+impl BettingStrategy for ParoliStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        match self.round.take() {
+            Some(net) if net > 0.0 => self.wins = (self.wins + 1) % self.max_wins,
+            Some(net) if net < 0.0 => self.wins = 0,
+            _ => {}
+        }
+        (self.unit * 2f32.powi(self.wins as i32)).min(remaining)
+    }
+
+    fn card_dealt(&mut self, _card: &Card) {}
+    fn shuffled(&mut self) {}
+
+    fn result(&mut self, outcome: &HandOutcome) {
+        self.round.record(outcome);
+    }
+}
+
+/// Raises the bet by one unit after a loss, and lowers it by one unit after a win
+pub struct DAlembertStrategy {
+    unit: f32,
+    current: f32,
+    round: Round,
+}
+
+impl DAlembertStrategy {
+    pub fn new(unit: f32) -> Self {
+        Self {
+            unit,
+            current: unit,
+            round: Round::default(),
+        }
+    }
+}
+
+impl BettingStrategy for DAlembertStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        match self.round.take() {
+            Some(net) if net < 0.0 => self.current += self.unit,
+            Some(net) if net > 0.0 => self.current = (self.current - self.unit).max(self.unit),
+            _ => {}
+        }
+        self.current.min(remaining)
+    }
+
+    fn card_dealt(&mut self, _card: &Card) {}
+    fn shuffled(&mut self) {}
+
+    fn result(&mut self, outcome: &HandOutcome) {
+        self.round.record(outcome);
+    }
+}
+
+/// Bets units following the Fibonacci sequence, moving one step forward after a loss and two
+/// steps back after a win
+pub struct FibonacciStrategy {
+    unit: f32,
+    step: usize,
+    round: Round,
+}
+
+impl FibonacciStrategy {
+    pub fn new(unit: f32) -> Self {
+        Self {
+            unit,
+            step: 0,
+            round: Round::default(),
+        }
+    }
+}
+
+impl BettingStrategy for FibonacciStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        match self.round.take() {
+            Some(net) if net < 0.0 => self.step += 1,
+            Some(net) if net > 0.0 => self.step = self.step.saturating_sub(2),
+            _ => {}
+        }
+
+        let (mut a, mut b) = (1.0, 1.0);
+        for _ in 0..self.step {
+            (a, b) = (b, a + b);
+        }
+        (self.unit * a).min(remaining)
+    }
+
+    fn card_dealt(&mut self, _card: &Card) {}
+    fn shuffled(&mut self) {}
+
+    fn result(&mut self, outcome: &HandOutcome) {
+        self.round.record(outcome);
+    }
+}
+
+/// Bets the sum of the first and last numbers of a line of units. A win crosses both numbers
+/// off, while a loss adds the lost bet to the end of the line. The line starts again once
+/// every number has been crossed off.
+pub struct LabouchereStrategy {
+    unit: f32,
+    sequence: Vec<f32>,
+    line: Vec<f32>,
+    round: Round,
+}
+
+impl LabouchereStrategy {
+    pub fn new(unit: f32, sequence: Vec<f32>) -> Self {
+        assert!(!sequence.is_empty(), "Labouchère sequence can't be empty");
+        Self {
+            unit,
+            line: sequence.clone(),
+            sequence,
+            round: Round::default(),
+        }
+    }
+
+    /// Current bet in units
+    fn units(&self) -> f32 {
+        match self.line.as_slice() {
+            [] => 0.0,
+            [only] => *only,
+            [first, .., last] => first + last,
+        }
+    }
+}
+
+impl BettingStrategy for LabouchereStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        match self.round.take() {
+            Some(net) if net > 0.0 => {
+                self.line.remove(0);
+                self.line.pop();
+            }
+            Some(net) if net < 0.0 => self.line.push(-net / self.unit),
+            _ => {}
+        }
+        if self.line.is_empty() {
+            self.line = self.sequence.clone();
+        }
+        (self.units() * self.unit).min(remaining)
+    }
+
+    fn card_dealt(&mut self, _card: &Card) {}
+    fn shuffled(&mut self) {}
+
+    fn result(&mut self, outcome: &HandOutcome) {
+        self.round.record(outcome);
+    }
+}
+
+/// Aims to win one unit per series. The bet rises by a unit after each win, without ever
+/// risking more than is needed to reach the goal, and stays the same after a loss.
+pub struct OscarsGrindStrategy {
+    unit: f32,
+    current: f32,
+    /// Profit so far in the current series
+    profit: f32,
+    round: Round,
+}
+
+impl OscarsGrindStrategy {
+    pub fn new(unit: f32) -> Self {
+        Self {
+            unit,
+            current: unit,
+            profit: 0.0,
+            round: Round::default(),
+        }
+    }
+}
+
+impl BettingStrategy for OscarsGrindStrategy {
+    fn bet(&mut self, remaining: f32) -> f32 {
+        if let Some(net) = self.round.take() {
+            self.profit += net;
+            if self.profit >= self.unit {
+                self.profit = 0.0;
+                self.current = self.unit;
+            } else if net > 0.0 {
+                self.current += self.unit;
+            }
+        }
+
+        let needed = self.unit - self.profit;
+        self.current.min(needed).max(self.unit).min(remaining)
+    }
+
+    fn card_dealt(&mut self, _card: &Card) {}
+    fn shuffled(&mut self) {}
+
+    fn result(&mut self, outcome: &HandOutcome) {
+        self.round.record(outcome);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RoundResult;
+
+    use super::*;
+
+    fn play(strategy: &mut dyn BettingStrategy, won: bool) -> f32 {
+        let bet = strategy.bet(f32::MAX);
+        strategy.result(&HandOutcome {
+            player: 0,
+            result: if won {
+                RoundResult::Win
+            } else {
+                RoundResult::Lose
+            },
+            bet,
+            wagered: bet,
+            net: if won { bet } else { -bet },
+            true_count: 0.0,
+        });
+        bet
+    }
+
+    #[test]
+    fn martingale() {
+        let mut strategy = MartingaleStrategy::new(10.0);
+        let bets: Vec<_> = [false, false, true, false]
+            .map(|won| play(&mut strategy, won))
+            .to_vec();
+        assert_eq!(bets, vec![10.0, 20.0, 40.0, 10.0]);
+    }
+
+    #[test]
+    fn paroli() {
+        let mut strategy = ParoliStrategy::new(10.0, NonZeroU32::new(3).unwrap());
+        let bets: Vec<_> = [true, true, true, true, false, true]
+            .map(|won| play(&mut strategy, won))
+            .to_vec();
+        // Back to one unit after three wins in a row, and after a loss
+        assert_eq!(bets, vec![10.0, 20.0, 40.0, 10.0, 20.0, 10.0]);
+    }
+
+    #[test]
+    fn dalembert() {
+        let mut strategy = DAlembertStrategy::new(10.0);
+        let bets: Vec<_> = [false, false, true, true, true, false]
+            .map(|won| play(&mut strategy, won))
+            .to_vec();
+        // Never drops below one unit
+        assert_eq!(bets, vec![10.0, 20.0, 30.0, 20.0, 10.0, 10.0]);
+    }
+
+    #[test]
+    fn fibonacci() {
+        let mut strategy = FibonacciStrategy::new(10.0);
+        let bets: Vec<_> = [false, false, false, false, true, true, false]
+            .map(|won| play(&mut strategy, won))
+            .to_vec();
+        // 1 1 2 3 5, then two steps back for each win
+        assert_eq!(bets, vec![10.0, 10.0, 20.0, 30.0, 50.0, 20.0, 10.0]);
+    }
+
+    #[test]
+    fn labouchere() {
+        let mut strategy = LabouchereStrategy::new(10.0, vec![1.0, 2.0, 3.0]);
+        let bets: Vec<_> = [false, true, true, true]
+            .map(|won| play(&mut strategy, won))
+            .to_vec();
+        // 1 2 3 -> 1 2 3 4 -> 2 3 -> (empty) -> 1 2 3
+        assert_eq!(bets, vec![40.0, 50.0, 50.0, 40.0]);
+    }
+
+    #[test]
+    fn oscars_grind() {
+        let mut strategy = OscarsGrindStrategy::new(10.0);
+        let bets: Vec<_> = [false, false, false, true, true, true]
+            .map(|won| play(&mut strategy, won))
+            .to_vec();
+        // Down 3 units, then after winning 1 and 2 units only 1 more is needed for the series
+        assert_eq!(bets, vec![10.0, 10.0, 10.0, 10.0, 20.0, 10.0]);
+    }
+}
@@ -0,0 +1,471 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::action::{
+    ActionStrategy, HumanActionStrategy, OptimalActionStrategy, SimpleActionStrategy,
+};
+use crate::ai::betting::{
+    BettingStrategy, EdgeEstimate, FlatBettingStrategy, HiLoCountingStrategy, HumanBettingStrategy,
+    KellyBettingStrategy, RampBettingStrategy, WongingStrategy,
+};
+use crate::ai::chart::ChartActionStrategy;
+use crate::ai::count::CountingSystem;
+use crate::ai::progression::{
+    DAlembertStrategy, FibonacciStrategy, LabouchereStrategy, MartingaleStrategy,
+    OscarsGrindStrategy, ParoliStrategy,
+};
+use crate::rules::Rules;
+
+/// A strategy chosen by name, along with its parameters.
+///
+/// Written either as just the name, e.g. `"optimal"`, or as a table with the name as its
+/// `type`, e.g. `{ type = "hilo", ramp = [1, 2, 4, 8] }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "SpecRepr", into = "SpecRepr")]
+pub struct StrategySpec {
+    pub name: String,
+    pub params: toml::Table,
+}
+
+impl StrategySpec {
+    /// Strategy with no parameters
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            params: toml::Table::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SpecRepr {
+    Name(String),
+    Table {
+        #[serde(rename = "type")]
+        name: String,
+        #[serde(flatten)]
+        params: toml::Table,
+    },
+}
+
+impl From<SpecRepr> for StrategySpec {
+    fn from(repr: SpecRepr) -> Self {
+        match repr {
+            SpecRepr::Name(name) => Self::new(&name),
+            SpecRepr::Table { name, params } => Self { name, params },
+        }
+    }
+}
+
+impl From<StrategySpec> for SpecRepr {
+    fn from(spec: StrategySpec) -> Self {
+        if spec.params.is_empty() {
+            SpecRepr::Name(spec.name)
+        } else {
+            SpecRepr::Table {
+                name: spec.name,
+                params: spec.params,
+            }
+        }
+    }
+}
+
+type Constructor<T> =
+    Box<dyn Fn(&toml::Table, &Rules, &Registry) -> Result<T, Box<dyn Error>> + Send + Sync>;
+
+/// Creates strategies from their names and parameters.
+///
+/// The default registry holds every built in strategy, and custom strategies can be added
+/// with [`Registry::register_action`] and [`Registry::register_betting`].
+pub struct Registry {
+    actions: HashMap<String, Constructor<Box<dyn ActionStrategy>>>,
+    betting: HashMap<String, Constructor<Box<dyn BettingStrategy>>>,
+}
+
+impl Registry {
+    /// Registry without any strategies
+    pub fn empty() -> Self {
+        Self {
+            actions: HashMap::new(),
+            betting: HashMap::new(),
+        }
+    }
+
+    /// Add an action strategy, created from parameters of type `P`
+    pub fn register_action<P: DeserializeOwned>(
+        &mut self,
+        name: &str,
+        constructor: impl Fn(P, &Rules, &Registry) -> Result<Box<dyn ActionStrategy>, Box<dyn Error>>
+        + Send
+        + Sync
+        + 'static,
+    ) {
+        self.actions
+            .insert(name.to_string(), with_params(name, constructor));
+    }
+
+    /// Add a betting strategy, created from parameters of type `P`
+    pub fn register_betting<P: DeserializeOwned>(
+        &mut self,
+        name: &str,
+        constructor: impl Fn(P, &Rules, &Registry) -> Result<Box<dyn BettingStrategy>, Box<dyn Error>>
+        + Send
+        + Sync
+        + 'static,
+    ) {
+        self.betting
+            .insert(name.to_string(), with_params(name, constructor));
+    }
+
+    pub fn action(
+        &self,
+        spec: &StrategySpec,
+        rules: &Rules,
+    ) -> Result<Box<dyn ActionStrategy>, Box<dyn Error>> {
+        let constructor = self.actions.get(&spec.name).ok_or_else(|| {
+            format!(
+                "unknown action strategy `{}`, expected one of {}",
+                spec.name,
+                names(&self.actions)
+            )
+        })?;
+        constructor(&spec.params, rules, self)
+    }
+
+    pub fn betting(
+        &self,
+        spec: &StrategySpec,
+        rules: &Rules,
+    ) -> Result<Box<dyn BettingStrategy>, Box<dyn Error>> {
+        let constructor = self.betting.get(&spec.name).ok_or_else(|| {
+            format!(
+                "unknown betting strategy `{}`, expected one of {}",
+                spec.name,
+                names(&self.betting)
+            )
+        })?;
+        constructor(&spec.params, rules, self)
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        registry.register_action("simple", |NoParams {}, _, _| {
+            Ok(Box::new(SimpleActionStrategy))
+        });
+        registry.register_action("optimal", |NoParams {}, rules, _| {
+            Ok(Box::new(OptimalActionStrategy::new(rules)))
+        });
+        registry.register_action("human", |NoParams {}, _, _| {
+            Ok(Box::new(HumanActionStrategy))
+        });
+        registry.register_action("chart", |params: ChartParams, rules, _| {
+            Ok(Box::new(ChartActionStrategy::load(params.path, *rules)?))
+        });
+
+        registry.register_betting("flat", |NoParams {}, _, _| {
+            Ok(Box::new(FlatBettingStrategy))
+        });
+        registry.register_betting("human", |params: UnitParams, rules, _| {
+            rules.validate()?;
+            Ok(Box::new(HumanBettingStrategy::new(
+                rules,
+                params.unit.unwrap_or(rules.min_bet),
+            )))
+        });
+        registry.register_betting("hilo", |params: HiLoParams, rules, _| {
+            if let Some(ramp) = &params.ramp {
+                require(!ramp.is_empty(), "hilo", "ramp can't be empty")?;
+            }
+            // Without a ramp the bet follows the running count rather than a number of units
+            require(
+                params.ramp.is_some() || params.unit.is_none(),
+                "hilo",
+                "unit needs a ramp",
+            )?;
+            Ok(match params.ramp {
+                Some(ramp) => Box::new(RampBettingStrategy::new(
+                    rules,
+                    CountingSystem::HI_LO,
+                    params.unit.unwrap_or(rules.min_bet),
+                    ramp,
+                )),
+                None => Box::new(HiLoCountingStrategy::new()),
+            })
+        });
+        registry.register_betting("ramp", |params: RampParams, rules, _| {
+            require(!params.ramp.is_empty(), "ramp", "ramp can't be empty")?;
+            Ok(Box::new(RampBettingStrategy::new(
+                rules,
+                system(&params.system)?,
+                params.unit.unwrap_or(rules.min_bet),
+                params.ramp,
+            )))
+        });
+        registry.register_betting("kelly", |params: KellyParams, rules, _| {
+            rules.validate()?;
+            require(params.fraction > 0.0, "kelly", "fraction must be above 0")?;
+            let unit = params.unit.unwrap_or(rules.min_bet);
+            require(unit > 0.0, "kelly", "unit must be above 0")?;
+            let default = EdgeEstimate::default();
+            let edge = EdgeEstimate {
+                intercept: params.intercept.unwrap_or(default.intercept),
+                slope: params.slope.unwrap_or(default.slope),
+            };
+            Ok(Box::new(KellyBettingStrategy::new(
+                rules,
+                system(&params.system)?,
+                edge,
+                params.fraction,
+                unit,
+            )))
+        });
+        registry.register_betting("wong", |params: WongParams, rules, registry| {
+            Ok(Box::new(WongingStrategy::new(
+                rules,
+                system(&params.system)?,
+                params.entry,
+                params.exit,
+                registry.betting(&params.betting, rules)?,
+            )))
+        });
+
+        registry.register_betting("martingale", |params: UnitParams, rules, _| {
+            Ok(Box::new(MartingaleStrategy::new(
+                params.unit.unwrap_or(rules.min_bet),
+            )))
+        });
+        registry.register_betting("paroli", |params: ParoliParams, rules, _| {
+            Ok(Box::new(ParoliStrategy::new(
+                params.unit.unwrap_or(rules.min_bet),
+                params.max_wins,
+            )))
+        });
+        registry.register_betting("dalembert", |params: UnitParams, rules, _| {
+            Ok(Box::new(DAlembertStrategy::new(
+                params.unit.unwrap_or(rules.min_bet),
+            )))
+        });
+        registry.register_betting("fibonacci", |params: UnitParams, rules, _| {
+            Ok(Box::new(FibonacciStrategy::new(
+                params.unit.unwrap_or(rules.min_bet),
+            )))
+        });
+        registry.register_betting("labouchere", |params: LabouchereParams, rules, _| {
+            require(
+                !params.sequence.is_empty(),
+                "labouchere",
+                "sequence can't be empty",
+            )?;
+            Ok(Box::new(LabouchereStrategy::new(
+                params.unit.unwrap_or(rules.min_bet),
+                params.sequence,
+            )))
+        });
+        registry.register_betting("oscars-grind", |params: UnitParams, rules, _| {
+            Ok(Box::new(OscarsGrindStrategy::new(
+                params.unit.unwrap_or(rules.min_bet),
+            )))
+        });
+
+        registry
+    }
+}
+
+/// Wraps a constructor to first parse its parameters
+fn with_params<P: DeserializeOwned, T>(
+    name: &str,
+    constructor: impl Fn(P, &Rules, &Registry) -> Result<T, Box<dyn Error>> + Send + Sync + 'static,
+) -> Constructor<T> {
+    let name = name.to_string();
+    Box::new(move |params, rules, registry| {
+        let params = toml::Value::Table(params.clone())
+            .try_into()
+            .map_err(|e| format!("invalid parameters for `{name}`: {e}"))?;
+        constructor(params, rules, registry)
+    })
+}
+
+fn names<T>(constructors: &HashMap<String, T>) -> String {
+    let mut names: Vec<_> = constructors.keys().map(String::as_str).collect();
+    names.sort();
+    names.join(", ")
+}
+
+/// Report `problem` with a strategy's parameters unless they are `valid`
+fn require(valid: bool, name: &str, problem: &str) -> Result<(), String> {
+    match valid {
+        true => Ok(()),
+        false => Err(format!("invalid parameters for `{name}`: {problem}")),
+    }
+}
+
+fn system(name: &str) -> Result<CountingSystem, String> {
+    CountingSystem::by_name(name).ok_or_else(|| format!("unknown counting system `{name}`"))
+}
+
+fn hi_lo() -> String {
+    CountingSystem::HI_LO.name.to_string()
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoParams {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChartParams {
+    path: PathBuf,
+}
+
+/// Unit bet, defaulting to the table minimum
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UnitParams {
+    unit: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HiLoParams {
+    unit: Option<f32>,
+    ramp: Option<Vec<f32>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RampParams {
+    #[serde(default = "hi_lo")]
+    system: String,
+    unit: Option<f32>,
+    ramp: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KellyParams {
+    #[serde(default = "hi_lo")]
+    system: String,
+    fraction: f32,
+    unit: Option<f32>,
+    intercept: Option<f32>,
+    slope: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WongParams {
+    #[serde(default = "hi_lo")]
+    system: String,
+    entry: f32,
+    exit: f32,
+    betting: StrategySpec,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParoliParams {
+    unit: Option<f32>,
+    max_wins: NonZeroU32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LabouchereParams {
+    unit: Option<f32>,
+    sequence: Vec<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Card;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Fixed {
+        amount: f32,
+    }
+
+    struct FixedBettingStrategy(f32);
+
+    impl BettingStrategy for FixedBettingStrategy {
+        fn bet(&mut self, remaining: f32) -> f32 {
+            self.0.min(remaining)
+        }
+
+        fn card_dealt(&mut self, _card: &Card) {}
+        fn shuffled(&mut self) {}
+    }
+
+    #[test]
+    fn strategies_from_specs() {
+        let rules = Rules::default();
+        let mut registry = Registry::default();
+        registry.register_betting("fixed", |params: Fixed, _, _| {
+            Ok(Box::new(FixedBettingStrategy(params.amount)))
+        });
+
+        let spec: StrategySpec = toml::from_str(
+            r#"
+            type = "wong"
+            entry = 1.0
+            exit = -1.0
+            betting = { type = "fixed", amount = 25.0 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(registry.betting(&spec, &rules).unwrap().bet(100.0), 25.0);
+
+        let spec = StrategySpec::new("martingale");
+        assert_eq!(registry.betting(&spec, &rules).unwrap().bet(100.0), 10.0);
+
+        let spec: StrategySpec = toml::from_str("type = \"paroli\"\nmax_win = 3").unwrap();
+        assert!(registry.betting(&spec, &rules).is_err());
+        assert!(
+            registry
+                .action(&StrategySpec::new("psychic"), &rules)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejected_specs() {
+        let crossed = Rules {
+            min_bet: 50.0,
+            max_bet: 10.0,
+            ..Rules::default()
+        };
+        let default = Rules::default();
+        let cases = [
+            ("type = \"paroli\"\nmax_wins = 0", &default, "max_wins"),
+            ("type = \"labouchere\"\nsequence = []", &default, "sequence"),
+            ("type = \"ramp\"\nramp = []", &default, "ramp"),
+            ("type = \"hilo\"\nramp = []", &default, "ramp"),
+            ("type = \"hilo\"\nunit = 25", &default, "unit needs a ramp"),
+            ("type = \"kelly\"\nfraction = 0.5", &crossed, "maximum bet"),
+            ("type = \"human\"", &crossed, "maximum bet"),
+            ("type = \"kelly\"\nfraction = 0.0", &default, "fraction"),
+            (
+                "type = \"kelly\"\nfraction = 0.5\nunit = 0.0",
+                &default,
+                "unit",
+            ),
+        ];
+        for (spec, rules, expected) in cases {
+            let parsed: StrategySpec = toml::from_str(spec).unwrap();
+            match Registry::default().betting(&parsed, rules) {
+                Ok(_) => panic!("{spec:?} was accepted"),
+                Err(e) => assert!(e.to_string().contains(expected), "{spec:?}: {e}"),
+            }
+        }
+    }
+}
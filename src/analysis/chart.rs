@@ -0,0 +1,177 @@
+use super::composition::rank;
+use super::{ActionValues, Analyzer, Composition};
+use crate::ai::chart::{Chart, ChartEntry, HARD_MIN, SOFT_MIN, SURRENDER_MIN, card, hand};
+use crate::rules::Rules;
+
+/// Derive a basic strategy chart for the rules from the exact expected value of each play.
+///
+/// Each total is decided on its two card hands from a full shoe, weighted by how likely each
+/// hand is to be dealt.
+pub fn generate(rules: &Rules) -> Chart {
+    let mut chart = Chart {
+        hard: [[ChartEntry::Hit; 10]; 17],
+        soft: [[ChartEntry::Hit; 10]; 9],
+        pairs: [[None; 10]; 10],
+        surrender: [[None; 10]; 6],
+    };
+
+    for (column, upcard) in (2..=11).map(card).enumerate() {
+        let mut analyzer = Analyzer::new(*rules, &upcard);
+        let mut shoe = Composition::new(rules.decks);
+        shoe.remove(rank(&upcard));
+
+        for (row, total) in (HARD_MIN..=20).enumerate() {
+            let mut hands: Vec<_> = (2..=10)
+                .filter_map(|a| {
+                    let b = total.checked_sub(a)?;
+                    (a < b && b <= 10).then_some([a, b])
+                })
+                .collect();
+            // Only reachable as a pair, which is then played as a total
+            if hands.is_empty() {
+                hands.push([total / 2, total / 2]);
+            }
+
+            let values = average(&mut analyzer, &shoe, &hands);
+            chart.hard[row][column] = entry(&values);
+            if (SURRENDER_MIN..SURRENDER_MIN + 6).contains(&total)
+                && let Some(surrender) = values.surrender
+                && surrender > playing(&values)
+            {
+                chart.surrender[(total - SURRENDER_MIN) as usize][column] =
+                    Some(ChartEntry::Surrender);
+            }
+        }
+
+        for (row, total) in (SOFT_MIN..=20).enumerate() {
+            let values = average(&mut analyzer, &shoe, &[[11, total - 11]]);
+            chart.soft[row][column] = entry(&values);
+        }
+
+        for (row, value) in (2..=11).enumerate() {
+            let values = analyzer.analyze(
+                &shoe_without(&shoe, &[value, value]),
+                &hand(&[value, value]),
+            );
+            if values.split.is_some_and(|split| split > playing(&values)) {
+                chart.pairs[row][column] = Some(ChartEntry::Split);
+            }
+        }
+    }
+
+    chart
+}
+
+/// Best value from hitting, standing or doubling, without splitting or surrendering
+fn playing(values: &ActionValues) -> f64 {
+    values
+        .stand
+        .max(values.hit)
+        .max(values.double.unwrap_or(f64::MIN))
+}
+
+fn entry(values: &ActionValues) -> ChartEntry {
+    let double = values.double.unwrap_or(f64::MIN);
+    if double > values.hit.max(values.stand) {
+        if values.hit > values.stand {
+            ChartEntry::Double
+        } else {
+            ChartEntry::DoubleOrStand
+        }
+    } else if values.hit > values.stand {
+        ChartEntry::Hit
+    } else {
+        ChartEntry::Stand
+    }
+}
+
+/// Values averaged across hands, weighted by the probability of each hand being dealt
+fn average(analyzer: &mut Analyzer, shoe: &Composition, hands: &[[u8; 2]]) -> ActionValues {
+    let mut average = ActionValues {
+        stand: 0.0,
+        hit: 0.0,
+        double: Some(0.0),
+        split: None,
+        surrender: None,
+    };
+    let mut total_weight = 0.0;
+
+    for cards in hands {
+        let remaining = shoe_without(shoe, cards);
+        let values = analyzer.analyze(&remaining, &hand(cards));
+        let first = rank(&card(cards[0]));
+        let second = rank(&card(cards[1]));
+        let weight =
+            shoe.count(first) as f64 * shoe_without(shoe, &cards[..1]).count(second) as f64;
+        total_weight += weight;
+
+        average.stand += weight * values.stand;
+        average.hit += weight * values.hit;
+        average.double = average
+            .double
+            .zip(values.double)
+            .map(|(a, b)| a + weight * b);
+        average.surrender = values.surrender;
+    }
+
+    average.stand /= total_weight;
+    average.hit /= total_weight;
+    average.double = average.double.map(|double| double / total_weight);
+    average
+}
+
+/// Shoe after dealing cards with blackjack values from 2 to 11
+fn shoe_without(shoe: &Composition, cards: &[u8]) -> Composition {
+    let mut shoe = *shoe;
+    cards
+        .iter()
+        .for_each(|&value| shoe.remove(rank(&card(value))));
+    shoe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Generator's decision for a single two card hand, without generating the whole chart
+    fn decide(rules: &Rules, cards: [u8; 2], dealer: u8) -> ActionValues {
+        let upcard = card(dealer);
+        let mut shoe = Composition::new(rules.decks);
+        shoe.remove(rank(&upcard));
+        let mut analyzer = Analyzer::new(*rules, &upcard);
+        if cards[0] == cards[1] {
+            analyzer.analyze(&shoe_without(&shoe, &cards), &hand(&cards))
+        } else {
+            average(&mut analyzer, &shoe, &[cards])
+        }
+    }
+
+    #[test]
+    fn six_deck_basic_strategy() {
+        let rules = Rules {
+            decks: 6,
+            dealer_peeks: true,
+            surrender: true,
+            ..Default::default()
+        };
+
+        assert_eq!(entry(&decide(&rules, [10, 2], 2)), ChartEntry::Hit);
+        assert_eq!(entry(&decide(&rules, [7, 5], 4)), ChartEntry::Stand);
+        assert_eq!(entry(&decide(&rules, [6, 5], 10)), ChartEntry::Double);
+        assert_eq!(entry(&decide(&rules, [11, 7], 9)), ChartEntry::Hit);
+        assert_eq!(
+            entry(&decide(&rules, [11, 7], 3)),
+            ChartEntry::DoubleOrStand
+        );
+
+        let values = decide(&rules, [10, 6], 10);
+        assert!(values.surrender.unwrap() > playing(&values));
+        let values = decide(&rules, [10, 4], 10);
+        assert!(values.surrender.unwrap() < playing(&values));
+
+        let values = decide(&rules, [8, 8], 10);
+        assert!(values.split.unwrap() > playing(&values));
+        let values = decide(&rules, [10, 10], 6);
+        assert!(values.split.unwrap() < playing(&values));
+    }
+}
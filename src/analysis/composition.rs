@@ -0,0 +1,57 @@
+use crate::cards::Card;
+use crate::cards::card::Face;
+
+/// Number of cards of each value in a shoe, indexed by value with aces counted as 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Composition {
+    counts: [u16; 10],
+}
+
+impl Composition {
+    /// Composition of a full, undealt shoe
+    pub fn new(decks: u8) -> Self {
+        let decks = decks as u16;
+        let mut counts = [4 * decks; 10];
+        counts[9] = 16 * decks;
+        Self { counts }
+    }
+
+    pub fn from_cards(cards: &[Card]) -> Self {
+        let mut composition = Self { counts: [0; 10] };
+        for card in cards {
+            composition.add(rank(card));
+        }
+        composition
+    }
+
+    pub fn count(&self, value: u8) -> u16 {
+        self.counts[value as usize - 1]
+    }
+
+    pub fn total(&self) -> u16 {
+        self.counts.iter().sum()
+    }
+
+    /// Probability that the next card drawn has the given value
+    pub fn probability(&self, value: u8) -> f64 {
+        self.count(value) as f64 / self.total() as f64
+    }
+
+    pub fn add(&mut self, value: u8) {
+        self.counts[value as usize - 1] += 1;
+    }
+
+    pub fn remove(&mut self, value: u8) {
+        let count = &mut self.counts[value as usize - 1];
+        assert!(*count > 0, "No cards of value {value} left to remove");
+        *count -= 1;
+    }
+}
+
+/// Value of a card with aces counted as 1, as used to index a `Composition`
+pub fn rank(card: &Card) -> u8 {
+    match card.face {
+        Face::Ace => 1,
+        face => face.value(),
+    }
+}
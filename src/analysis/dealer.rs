@@ -0,0 +1,91 @@
+use super::Composition;
+use crate::rules::Rules;
+
+/// Probabilities of each final dealer outcome for a given upcard
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DealerProbabilities {
+    /// Probability of the dealer finishing on each total from 17 to 21
+    pub totals: [f64; 5],
+    pub bust: f64,
+    /// Always zero when the dealer peeks, as the outcomes are then conditioned on no blackjack
+    pub blackjack: f64,
+}
+
+impl DealerProbabilities {
+    /// Calculate the dealer's outcomes when drawing from `shoe`, which must not include the upcard
+    pub fn new(rules: &Rules, shoe: &Composition, upcard: u8) -> Self {
+        let mut probabilities = Self::default();
+        let mut shoe = *shoe;
+        draw(
+            rules,
+            &mut shoe,
+            DealerHand {
+                total: upcard,
+                ace: upcard == 1,
+                cards: 1,
+            },
+            1.0,
+            &mut probabilities,
+        );
+        probabilities
+    }
+}
+
+struct DealerHand {
+    total: u8,
+    ace: bool,
+    cards: u8,
+}
+
+fn draw(
+    rules: &Rules,
+    shoe: &mut Composition,
+    hand: DealerHand,
+    probability: f64,
+    out: &mut DealerProbabilities,
+) {
+    let soft = hand.ace && hand.total + 10 <= 21;
+    let value = if soft { hand.total + 10 } else { hand.total };
+
+    if hand.cards == 2 && value == 21 {
+        out.blackjack += probability;
+        return;
+    }
+    if value > 21 {
+        out.bust += probability;
+        return;
+    }
+    if value > 17 || (value == 17 && !(soft && rules.dealer_hits_soft_17)) {
+        out.totals[value as usize - 17] += probability;
+        return;
+    }
+
+    // A peeking dealer has already shown the hole card can't make a blackjack
+    let excluded = match (rules.dealer_peeks, hand.cards, hand.total) {
+        (true, 1, 1) => Some(10),
+        (true, 1, 10) => Some(1),
+        _ => None,
+    };
+    let remaining = shoe.total() - excluded.map_or(0, |v| shoe.count(v));
+
+    for value in 1..=10 {
+        let count = shoe.count(value);
+        if count == 0 || excluded == Some(value) {
+            continue;
+        }
+
+        shoe.remove(value);
+        draw(
+            rules,
+            shoe,
+            DealerHand {
+                total: hand.total + value,
+                ace: hand.ace || value == 1,
+                cards: hand.cards + 1,
+            },
+            probability * count as f64 / remaining as f64,
+            out,
+        );
+        shoe.add(value);
+    }
+}
@@ -0,0 +1,200 @@
+use super::composition::rank;
+use super::removal::effects_of_removal;
+use super::{ActionValues, Analyzer, Composition};
+use crate::ai::chart::{card, hand};
+use crate::ai::count::CountingSystem;
+use crate::risk::normal_cdf;
+use crate::rules::Rules;
+
+/// How well a counting system's tags capture the effects of removal of each card
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountEfficiency {
+    /// Correlation between the tags and the effect of each card on the player's expectation,
+    /// which measures how well the count tracks the edge for betting
+    pub betting_correlation: f64,
+    /// Correlation between the tags and the effect of each card on common strategy variations,
+    /// weighted by what each variation can gain, which measures how well the count guides
+    /// changes in play
+    pub playing_efficiency: f64,
+}
+
+impl CountEfficiency {
+    pub fn new(system: &CountingSystem, rules: &Rules) -> Self {
+        Self::with_effects(system, rules, &effects_of_removal(rules))
+    }
+
+    /// Efficiency using effects of removal that have already been calculated for the rules
+    pub fn with_effects(system: &CountingSystem, rules: &Rules, effects: &[f64; 10]) -> Self {
+        Self {
+            betting_correlation: betting_correlation(system, effects),
+            playing_efficiency: playing_efficiency(system, rules),
+        }
+    }
+}
+
+/// Correlation between a system's tags and the effects of removal, with aces first and ten
+/// valued cards last
+pub fn betting_correlation(system: &CountingSystem, effects: &[f64; 10]) -> f64 {
+    correlation(&system.tags.map(|tag| tag as f64), effects)
+}
+
+/// Strategy variations as the player's hand, the dealer's upcard, and the two plays being
+/// decided between. Cards use blackjack values, with aces as 11.
+const VARIATIONS: [([u8; 2], u8, Play, Play); 14] = [
+    ([10, 6], 10, Play::Stand, Play::Hit),
+    ([10, 5], 10, Play::Stand, Play::Hit),
+    ([10, 6], 9, Play::Stand, Play::Hit),
+    ([10, 2], 2, Play::Stand, Play::Hit),
+    ([10, 2], 3, Play::Stand, Play::Hit),
+    ([10, 2], 4, Play::Stand, Play::Hit),
+    ([10, 3], 2, Play::Stand, Play::Hit),
+    ([10, 3], 3, Play::Stand, Play::Hit),
+    ([6, 4], 10, Play::Double, Play::Hit),
+    ([6, 4], 11, Play::Double, Play::Hit),
+    ([6, 5], 11, Play::Double, Play::Hit),
+    ([5, 4], 2, Play::Double, Play::Hit),
+    ([5, 4], 7, Play::Double, Play::Hit),
+    ([10, 10], 6, Play::Split, Play::Stand),
+];
+
+#[derive(Clone, Copy)]
+enum Play {
+    Stand,
+    Hit,
+    Double,
+    Split,
+}
+
+impl Play {
+    fn value(self, values: &ActionValues) -> f64 {
+        match self {
+            Play::Stand => values.stand,
+            Play::Hit => values.hit,
+            Play::Double => values.double.unwrap(),
+            Play::Split => values.split.unwrap(),
+        }
+    }
+}
+
+/// Cards seen before a variation is played, for how far the count has moved from zero
+const CARDS_SEEN: f64 = 26.0;
+
+/// Correlation between a system's tags and the effects of removal on the gain from each of a
+/// set of common strategy variations, following Griffin over a shorter list of variations.
+///
+/// Each variation is weighted by how often its hand is dealt and how much a player who knew
+/// the exact gain would win by varying, halfway through a single deck. The gain after the
+/// cards seen is taken to be normal, with a spread from the effects of removal.
+pub fn playing_efficiency(system: &CountingSystem, rules: &Rules) -> f64 {
+    let rules = Rules { decks: 1, ..*rules };
+    let tags = system.tags.map(|tag| tag as f64);
+
+    let (mut total, mut total_weight) = (0.0, 0.0);
+    for &(cards, upcard, play, alternative) in &VARIATIONS {
+        let upcard = card(upcard);
+        let hand = hand(&cards);
+        let mut shoe = Composition::new(rules.decks);
+        let mut frequency = if cards[0] == cards[1] { 1.0 } else { 2.0 };
+        for card in [&upcard].into_iter().chain(&hand.cards) {
+            frequency *= shoe.probability(rank(card));
+            shoe.remove(rank(card));
+        }
+
+        let mut analyzer = Analyzer::new(rules, &upcard);
+        let gain = |analyzer: &mut Analyzer, shoe: &Composition| {
+            let values = analyzer.analyze(shoe, &hand);
+            play.value(&values) - alternative.value(&values)
+        };
+        let base = gain(&mut analyzer, &shoe);
+        let effects: Vec<_> = (1..=10)
+            .map(|rank| {
+                let mut shoe = shoe;
+                shoe.remove(rank);
+                gain(&mut analyzer, &shoe) - base
+            })
+            .collect();
+
+        // Spread of the gain after drawing the cards seen from the rest of the shoe
+        let cards = shoe.total() as f64;
+        let spread =
+            (CARDS_SEEN * (cards - CARDS_SEEN) / (cards - 1.0) * variance(&effects)).sqrt();
+        let weight = frequency * varying_gain(base, spread);
+        total += weight * correlation(&tags, &effects);
+        total_weight += weight;
+    }
+    total / total_weight
+}
+
+/// Expected gain from switching plays whenever it's better, where the gain from the play
+/// over its alternative is normal with mean `base` and standard deviation `spread`
+fn varying_gain(base: f64, spread: f64) -> f64 {
+    if spread == 0.0 {
+        return 0.0;
+    }
+    let z = base.abs() / spread;
+    let density = (-z * z / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    spread * density - base.abs() * normal_cdf(-z)
+}
+
+/// Variance across the cards in a deck, where each ten valued card has the same value
+fn variance(values: &[f64]) -> f64 {
+    let weight = |i: usize| if i == 9 { 4.0 } else { 1.0 };
+    let mean = values
+        .iter()
+        .enumerate()
+        .map(|(i, x)| weight(i) * x)
+        .sum::<f64>()
+        / 13.0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, x)| weight(i) * (x - mean).powi(2))
+        .sum::<f64>()
+        / 13.0
+}
+
+/// Correlation across card values, weighting ten valued cards by how many there are in a deck
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let weight = |i: usize| if i == 9 { 4.0 } else { 1.0 };
+    let mean = |x: &[f64]| {
+        x.iter()
+            .enumerate()
+            .map(|(i, x)| weight(i) * x)
+            .sum::<f64>()
+            / 13.0
+    };
+    let (mean_a, mean_b) = (mean(a), mean(b));
+
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for i in 0..a.len() {
+        let (da, db) = (a[i] - mean_a, b[i] - mean_b);
+        covariance += weight(i) * da * db;
+        variance_a += weight(i) * da * da;
+        variance_b += weight(i) * db * db;
+    }
+    covariance / (variance_a * variance_b).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn betting_correlation_of_systems() {
+        // Griffin's single deck effects of removal, in percent
+        let effects = [-0.61, 0.38, 0.44, 0.55, 0.69, 0.46, 0.28, 0.0, -0.18, -0.51];
+        let hi_lo = betting_correlation(&CountingSystem::HI_LO, &effects);
+        assert!((hi_lo - 0.97).abs() < 0.01, "{hi_lo}");
+        let hi_opt = betting_correlation(&CountingSystem::HI_OPT_I, &effects);
+        assert!((hi_opt - 0.88).abs() < 0.01, "{hi_opt}");
+    }
+
+    #[test]
+    fn playing_efficiency_of_systems() {
+        let rules = Rules::default();
+        let hi_lo = playing_efficiency(&CountingSystem::HI_LO, &rules);
+        let hi_opt_ii = playing_efficiency(&CountingSystem::HI_OPT_II, &rules);
+        assert!(hi_lo > 0.4 && hi_lo < 0.8, "{hi_lo}");
+        assert!(hi_opt_ii > hi_lo, "{hi_opt_ii} vs {hi_lo}");
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use super::composition::rank;
+use super::{Composition, DealerProbabilities};
+use crate::cards::{Card, Hand};
+use crate::player::Action;
+use crate::rules::Rules;
+
+/// Expected value of each action for a hand, in units of the initial bet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionValues {
+    pub stand: f64,
+    pub hit: f64,
+    /// Only available on the first two cards of a hand
+    pub double: Option<f64>,
+    /// Only available on a pair
+    pub split: Option<f64>,
+    /// Only available on the first two cards when the rules allow surrender
+    pub surrender: Option<f64>,
+}
+
+impl ActionValues {
+    /// Expected value of `action`, if it's available
+    pub fn value(&self, action: Action) -> Option<f64> {
+        match action {
+            Action::Stand => Some(self.stand),
+            Action::Hit => Some(self.hit),
+            Action::Double => self.double,
+            Action::Split => self.split,
+            Action::Surrender => self.surrender,
+        }
+    }
+
+    /// The action with the highest expected value
+    pub fn best(&self) -> (Action, f64) {
+        let mut best = (Action::Stand, self.stand);
+        let options = [
+            (Action::Hit, Some(self.hit)),
+            (Action::Double, self.double),
+            (Action::Split, self.split),
+            (Action::Surrender, self.surrender),
+        ];
+        for (action, ev) in options {
+            if let Some(ev) = ev
+                && ev > best.1
+            {
+                best = (action, ev);
+            }
+        }
+        best
+    }
+}
+
+/// Calculates exact expected values against a single dealer upcard.
+///
+/// Results are cached by shoe composition, so a single analyzer should be reused when
+/// analysing many hands against the same upcard.
+pub struct Analyzer {
+    rules: Rules,
+    upcard: u8,
+    dealer_cache: HashMap<Composition, DealerProbabilities>,
+    hit_cache: HashMap<(Composition, PlayerHand), f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PlayerHand {
+    /// Hard total, counting aces as 1
+    total: u8,
+    ace: bool,
+}
+
+impl PlayerHand {
+    fn new(cards: &[u8]) -> Self {
+        Self {
+            total: cards.iter().sum(),
+            ace: cards.contains(&1),
+        }
+    }
+
+    fn draw(self, value: u8) -> Self {
+        Self {
+            total: self.total + value,
+            ace: self.ace || value == 1,
+        }
+    }
+
+    fn value(&self) -> u8 {
+        if self.ace && self.total + 10 <= 21 {
+            self.total + 10
+        } else {
+            self.total
+        }
+    }
+}
+
+impl Analyzer {
+    pub fn new(rules: Rules, upcard: &Card) -> Self {
+        Self {
+            rules,
+            upcard: rank(upcard),
+            dealer_cache: HashMap::new(),
+            hit_cache: HashMap::new(),
+        }
+    }
+
+    /// Dealer outcomes when drawing from `shoe`, which must exclude the upcard
+    pub fn dealer(&mut self, shoe: &Composition) -> DealerProbabilities {
+        if let Some(probabilities) = self.dealer_cache.get(shoe) {
+            return *probabilities;
+        }
+        let probabilities = DealerProbabilities::new(&self.rules, shoe, self.upcard);
+        self.dealer_cache.insert(*shoe, probabilities);
+        probabilities
+    }
+
+    /// Expected value of each action for `hand`.
+    ///
+    /// `shoe` holds the unseen cards, so must exclude both the hand and the dealer's upcard.
+    /// When the dealer peeks, values are conditioned on the dealer not having blackjack.
+    /// Split values assume no further resplits, and that the two split hands don't affect
+    /// each other's draws.
+    pub fn analyze(&mut self, shoe: &Composition, hand: &Hand) -> ActionValues {
+        let cards: Vec<u8> = hand.cards.iter().map(rank).collect();
+        let player = PlayerHand::new(&cards);
+        let initial = cards.len() == 2;
+
+        let stand = if initial && player.value() == 21 {
+            let dealer = self.dealer(shoe);
+            self.rules.blackjack_payout as f64 * (1.0 - dealer.blackjack)
+        } else {
+            self.stand(shoe, player)
+        };
+
+        ActionValues {
+            stand,
+            hit: self.hit(shoe, player),
+            double: initial.then(|| self.double(shoe, player)),
+            split: (initial && cards[0] == cards[1]).then(|| self.split(shoe, cards[0])),
+            surrender: (initial && self.rules.surrender).then_some(-0.5),
+        }
+    }
+
+    fn stand(&mut self, shoe: &Composition, player: PlayerHand) -> f64 {
+        let value = player.value();
+        if value > 21 {
+            return -1.0;
+        }
+
+        let dealer = self.dealer(shoe);
+        let mut ev = dealer.bust - dealer.blackjack;
+        for (total, probability) in (17..=21).zip(dealer.totals) {
+            if value > total {
+                ev += probability;
+            } else if value < total {
+                ev -= probability;
+            }
+        }
+        ev
+    }
+
+    /// Value of hitting, then continuing to play optimally
+    fn hit(&mut self, shoe: &Composition, player: PlayerHand) -> f64 {
+        if let Some(ev) = self.hit_cache.get(&(*shoe, player)) {
+            return *ev;
+        }
+
+        let mut ev = 0.0;
+        self.each_draw(shoe, |analyzer, shoe, value, probability| {
+            let player = player.draw(value);
+            let outcome = if player.value() > 21 {
+                -1.0
+            } else if player.value() == 21 {
+                analyzer.stand(shoe, player)
+            } else {
+                analyzer.stand(shoe, player).max(analyzer.hit(shoe, player))
+            };
+            ev += probability * outcome;
+        });
+
+        self.hit_cache.insert((*shoe, player), ev);
+        ev
+    }
+
+    fn double(&mut self, shoe: &Composition, player: PlayerHand) -> f64 {
+        let mut ev = 0.0;
+        self.each_draw(shoe, |analyzer, shoe, value, probability| {
+            ev += probability * analyzer.stand(shoe, player.draw(value));
+        });
+        2.0 * ev
+    }
+
+    fn split(&mut self, shoe: &Composition, card: u8) -> f64 {
+        let das = self.rules.double_after_split;
+        let mut ev = 0.0;
+        self.each_draw(shoe, |analyzer, shoe, value, probability| {
+            let player = PlayerHand::new(&[card, value]);
+            let mut outcome = analyzer.stand(shoe, player);
+            // Split aces only receive one card
+            if card != 1 {
+                outcome = outcome.max(analyzer.hit(shoe, player));
+                if das {
+                    outcome = outcome.max(analyzer.double(shoe, player));
+                }
+            }
+            ev += probability * outcome;
+        });
+        2.0 * ev
+    }
+
+    /// Calls `f` with the shoe after drawing each possible card value, and the probability of
+    /// drawing it
+    fn each_draw(
+        &mut self,
+        shoe: &Composition,
+        mut f: impl FnMut(&mut Self, &Composition, u8, f64),
+    ) {
+        for value in 1..=10 {
+            if shoe.count(value) == 0 {
+                continue;
+            }
+            let probability = shoe.probability(value);
+            let mut shoe = *shoe;
+            shoe.remove(value);
+            f(self, &shoe, value, probability);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cards::card::{Face, Suit};
+
+    use super::*;
+
+    fn card(face: Face) -> Card {
+        Card {
+            suit: Suit::Spade,
+            face,
+        }
+    }
+
+    fn analyze(rules: Rules, player: [Face; 2], upcard: Face) -> ActionValues {
+        let hand = Hand {
+            cards: player.map(card).to_vec(),
+        };
+        let upcard = card(upcard);
+        let mut shoe = Composition::new(rules.decks);
+        hand.cards.iter().for_each(|c| shoe.remove(rank(c)));
+        shoe.remove(rank(&upcard));
+        Analyzer::new(rules, &upcard).analyze(&shoe, &hand)
+    }
+
+    #[test]
+    fn dealer_probabilities_sum_to_one() {
+        let rules = Rules {
+            decks: 6,
+            ..Default::default()
+        };
+        for upcard in 1..=10 {
+            let mut shoe = Composition::new(rules.decks);
+            shoe.remove(upcard);
+            let dealer = DealerProbabilities::new(&rules, &shoe, upcard);
+            let total: f64 = dealer.totals.iter().sum::<f64>() + dealer.bust + dealer.blackjack;
+            assert!((total - 1.0).abs() < 1e-9, "upcard {upcard}: {total}");
+        }
+    }
+
+    #[test]
+    fn peek_excludes_blackjack() {
+        let rules = Rules {
+            decks: 6,
+            dealer_peeks: true,
+            ..Default::default()
+        };
+        let mut shoe = Composition::new(rules.decks);
+        shoe.remove(1);
+        let dealer = DealerProbabilities::new(&rules, &shoe, 1);
+        assert_eq!(dealer.blackjack, 0.0);
+    }
+
+    #[test]
+    fn stiff_against_ten() {
+        let rules = Rules {
+            decks: 6,
+            dealer_peeks: true,
+            ..Default::default()
+        };
+        let values = analyze(rules, [Face::Ten, Face::Six], Face::Ten);
+        // Well known values for 16 v 10 in a six deck shoe
+        assert!((values.stand - -0.540).abs() < 0.01, "{values:?}");
+        assert!((values.hit - -0.535).abs() < 0.01, "{values:?}");
+    }
+
+    #[test]
+    fn best_action() {
+        let rules = Rules {
+            decks: 6,
+            dealer_peeks: true,
+            ..Default::default()
+        };
+        let values = analyze(rules, [Face::Six, Face::Five], Face::Six);
+        assert_eq!(values.best().0, Action::Double);
+
+        let values = analyze(rules, [Face::Eight, Face::Eight], Face::Ten);
+        assert_eq!(values.best().0, Action::Split);
+
+        let values = analyze(rules, [Face::Ten, Face::Ten], Face::Six);
+        assert_eq!(values.best().0, Action::Stand);
+    }
+}
@@ -0,0 +1,13 @@
+pub mod chart;
+pub mod composition;
+pub mod dealer;
+pub mod efficiency;
+pub mod ev;
+pub mod removal;
+
+pub use chart::generate as generate_chart;
+pub use composition::Composition;
+pub use dealer::DealerProbabilities;
+pub use efficiency::CountEfficiency;
+pub use ev::{ActionValues, Analyzer};
+pub use removal::{effects_of_removal, expected_value};
@@ -0,0 +1,125 @@
+use super::{ActionValues, Analyzer, Composition};
+use crate::ai::chart::{card, hand};
+use crate::player::Action;
+use crate::rules::Rules;
+
+/// Player's expected value per initial bet for a round dealt from `shoe`, playing each hand
+/// perfectly for the shoe
+pub fn expected_value(rules: &Rules, shoe: &Composition) -> f64 {
+    round_value(rules, shoe, |_, values| values.best().1)
+}
+
+/// Change in the player's expected value from removing a single card of each value from a
+/// full deck, with aces first and ten valued cards last.
+///
+/// The first decision on each hand is the one made for the full deck, as a player following
+/// basic strategy would, so the effects sum to about zero across the deck. They are calculated
+/// for a single deck as is conventional, since the effects scale with the number of decks
+/// without changing shape.
+pub fn effects_of_removal(rules: &Rules) -> [f64; 10] {
+    let rules = Rules { decks: 1, ..*rules };
+    let shoe = Composition::new(1);
+    let mut decisions = [Action::Stand; 1000];
+    let base = round_value(&rules, &shoe, |index, values| {
+        let (action, ev) = values.best();
+        decisions[index] = action;
+        ev
+    });
+
+    let mut effects = [0.0; 10];
+    for (rank, effect) in (1..=10).zip(&mut effects) {
+        let mut shoe = shoe;
+        shoe.remove(rank);
+        let ev = round_value(&rules, &shoe, |index, values| {
+            values
+                .value(decisions[index])
+                .unwrap_or_else(|| values.best().1)
+        });
+        *effect = ev - base;
+    }
+    effects
+}
+
+/// Expected value of a round dealt from `shoe`, where `play` gives the value of each two card
+/// hand from its analyzed values. Hands are indexed by the ranks of the upcard and the player's
+/// two cards.
+fn round_value(
+    rules: &Rules,
+    shoe: &Composition,
+    mut play: impl FnMut(usize, &ActionValues) -> f64,
+) -> f64 {
+    let mut total = 0.0;
+    for upcard in 1..=10 {
+        if shoe.count(upcard) == 0 {
+            continue;
+        }
+        let upcard_probability = shoe.probability(upcard);
+        let mut shoe = *shoe;
+        shoe.remove(upcard);
+        let mut analyzer = Analyzer::new(*rules, &card(value(upcard)));
+
+        for first in 1..=10 {
+            if shoe.count(first) == 0 {
+                continue;
+            }
+            let first_probability = shoe.probability(first);
+            let mut shoe = shoe;
+            shoe.remove(first);
+
+            for second in 1..=10 {
+                if shoe.count(second) == 0 {
+                    continue;
+                }
+                let probability = upcard_probability * first_probability * shoe.probability(second);
+                let mut shoe = shoe;
+                shoe.remove(second);
+
+                let player_blackjack = first + second == 11 && (first == 1 || second == 1);
+                let values = analyzer.analyze(&shoe, &hand(&[value(first), value(second)]));
+                let index =
+                    (upcard as usize - 1) * 100 + (first as usize - 1) * 10 + second as usize - 1;
+                let mut ev = play(index, &values);
+
+                // Analyzed values are conditioned on the dealer not having blackjack after a peek
+                if rules.dealer_peeks && (upcard == 1 || upcard == 10) {
+                    let hole = if upcard == 1 { 10 } else { 1 };
+                    let blackjack = shoe.probability(hole);
+                    let lost = if player_blackjack { 0.0 } else { -1.0 };
+                    ev = blackjack * lost + (1.0 - blackjack) * ev;
+                }
+                total += probability * ev;
+            }
+        }
+    }
+    total
+}
+
+/// Blackjack value of a card from its rank, counting aces as 11
+fn value(rank: u8) -> u8 {
+    if rank == 1 { 11 } else { rank }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_deck_effects() {
+        // Griffin's rules: the dealer stands on soft 17 and peeks, without doubling after splits
+        let rules = Rules {
+            dealer_peeks: true,
+            double_after_split: false,
+            ..Rules::default()
+        };
+        let effects = effects_of_removal(&rules).map(|effect| effect * 100.0);
+        // Griffin gives +0.69% for a five and -0.61% for an ace, with basic strategy throughout
+        // rather than only for the first decision
+        assert!((effects[4] - 0.67).abs() < 0.05, "{effects:?}");
+        assert!((effects[0] + 0.59).abs() < 0.05, "{effects:?}");
+        assert!(effects[9] < -0.45, "{effects:?}");
+
+        // The effects of removing the whole deck cancel out, with four ten valued ranks
+        let sum = effects[..9].iter().sum::<f64>() + 4.0 * effects[9];
+        assert!(sum.abs() < 0.15, "{sum}");
+    }
+}
@@ -0,0 +1,69 @@
+use std::error::Error;
+
+use midas::analysis::composition::rank;
+use midas::analysis::{Analyzer, Composition};
+use midas::{Card, Experiment, Face, Hand, Suit};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Dealer's upcard, e.g. `A` or `10`
+    #[arg(long, value_parser = parse_card)]
+    dealer: Card,
+    /// Cards in the player's hand, e.g. `10 6`
+    #[arg(required = true, num_args = 2.., value_parser = parse_card)]
+    hand: Vec<Card>,
+}
+
+pub fn run(experiment: &Experiment, args: &Args) -> Result<(), Box<dyn Error>> {
+    let hand = Hand {
+        cards: args.hand.clone(),
+    };
+    let mut shoe = Composition::new(experiment.rules.decks);
+    for card in hand.cards.iter().chain([&args.dealer]) {
+        if shoe.count(rank(card)) == 0 {
+            return Err(format!("not enough {:?}s in the shoe", card.face).into());
+        }
+        shoe.remove(rank(card));
+    }
+
+    let values = Analyzer::new(experiment.rules, &args.dealer).analyze(&shoe, &hand);
+    println!("{} ({}) against {}", hand, hand.value(), args.dealer);
+    println!("Stand:     {:+.4}", values.stand);
+    println!("Hit:       {:+.4}", values.hit);
+    let options = [
+        ("Double:   ", values.double),
+        ("Split:    ", values.split),
+        ("Surrender:", values.surrender),
+    ];
+    for (name, value) in options {
+        if let Some(value) = value {
+            println!("{name} {value:+.4}");
+        }
+    }
+    let (action, value) = values.best();
+    println!("Best: {action:?} ({value:+.4})");
+    Ok(())
+}
+
+fn parse_card(s: &str) -> Result<Card, String> {
+    let face = match s.to_ascii_uppercase().as_str() {
+        "A" => Face::Ace,
+        "2" => Face::Two,
+        "3" => Face::Three,
+        "4" => Face::Four,
+        "5" => Face::Five,
+        "6" => Face::Six,
+        "7" => Face::Seven,
+        "8" => Face::Eight,
+        "9" => Face::Nine,
+        "10" | "T" => Face::Ten,
+        "J" => Face::Jack,
+        "Q" => Face::Queen,
+        "K" => Face::King,
+        _ => return Err(format!("unknown card `{s}`, expected 2-10, J, Q, K or A")),
+    };
+    Ok(Card {
+        suit: Suit::Spade,
+        face,
+    })
+}
@@ -0,0 +1,22 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use midas::{Experiment, analysis};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to save the chart to, instead of printing it
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+/// Print the basic strategy chart for the rules, which the optimal strategy plays
+pub fn run(experiment: &Experiment, args: &Args) -> Result<(), Box<dyn Error>> {
+    let chart = analysis::generate_chart(&experiment.rules);
+    match &args.output {
+        Some(path) => fs::write(path, chart.to_string())?,
+        None => print!("{chart}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use midas::{Experiment, Seat};
+
+/// Table settings that can be given as flags, overriding the experiment file
+#[derive(clap::Args)]
+pub struct TableArgs {
+    /// Number of decks in the shoe
+    #[arg(long, global = true)]
+    decks: Option<u8>,
+    /// Fraction of the shoe dealt before reshuffling
+    #[arg(long, global = true)]
+    penetration: Option<f32>,
+    /// Cards burned after each shuffle
+    #[arg(long, global = true)]
+    burn: Option<u8>,
+    /// Dealer hits soft 17
+    #[arg(long, global = true, overrides_with = "no_h17")]
+    h17: bool,
+    /// Dealer stands on soft 17
+    #[arg(long, global = true, overrides_with = "h17")]
+    no_h17: bool,
+    /// Payout ratio for a blackjack, e.g. 1.2 for 6:5
+    #[arg(long, global = true)]
+    payout: Option<f32>,
+    /// Dealer peeks for blackjack
+    #[arg(long, global = true, overrides_with = "no_peek")]
+    peek: bool,
+    /// Dealer doesn't peek for blackjack
+    #[arg(long, global = true, overrides_with = "peek")]
+    no_peek: bool,
+    /// Late surrender is allowed
+    #[arg(long, global = true, overrides_with = "no_surrender")]
+    surrender: bool,
+    /// Surrender isn't allowed
+    #[arg(long, global = true, overrides_with = "surrender")]
+    no_surrender: bool,
+    /// Doubling after a split is allowed
+    #[arg(long, global = true, overrides_with = "no_das")]
+    das: bool,
+    /// Doubling after a split isn't allowed
+    #[arg(long, global = true, overrides_with = "das")]
+    no_das: bool,
+    #[arg(long, global = true)]
+    min_bet: Option<f32>,
+    #[arg(long, global = true)]
+    max_bet: Option<f32>,
+    /// Number of seats at the table
+    #[arg(long, global = true)]
+    seats: Option<usize>,
+    /// Player as `action:betting:bankroll`, e.g. `optimal:hilo:1000`, optionally followed by
+    /// `:name`. Repeat for more players
+    #[arg(long = "player", global = true)]
+    players: Vec<Seat>,
+    /// Seed for shuffling, random if not given
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+}
+
+impl TableArgs {
+    /// Override the experiment's table with any flags given, checking the rules that result
+    pub fn apply(&self, experiment: &mut Experiment) -> Result<(), String> {
+        let rules = &mut experiment.rules;
+        if let Some(decks) = self.decks {
+            rules.decks = decks;
+        }
+        if let Some(penetration) = self.penetration {
+            rules.penetration = penetration;
+        }
+        if let Some(burn) = self.burn {
+            rules.burn_cards = burn;
+        }
+        if let Some(payout) = self.payout {
+            rules.blackjack_payout = payout;
+        }
+        if let Some(min_bet) = self.min_bet {
+            rules.min_bet = min_bet;
+        }
+        if let Some(max_bet) = self.max_bet {
+            rules.max_bet = max_bet;
+        }
+        if let Some(seats) = self.seats {
+            rules.seats = seats;
+        }
+        if let Some(h17) = flag(self.h17, self.no_h17) {
+            rules.dealer_hits_soft_17 = h17;
+        }
+        if let Some(peek) = flag(self.peek, self.no_peek) {
+            rules.dealer_peeks = peek;
+        }
+        if let Some(surrender) = flag(self.surrender, self.no_surrender) {
+            rules.surrender = surrender;
+        }
+        if let Some(das) = flag(self.das, self.no_das) {
+            rules.double_after_split = das;
+        }
+
+        if !self.players.is_empty() {
+            experiment.seats = self.players.clone();
+        }
+        if self.seed.is_some() {
+            experiment.run.seed = self.seed;
+        }
+        experiment.rules.validate()
+    }
+}
+
+/// Setting given by a `--flag` and `--no-flag` pair, if either was used. The last one given
+/// overrides the other.
+fn flag(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+/// Seat `default` players if the experiment doesn't have any
+pub fn default_seats(experiment: &mut Experiment, default: &[&str]) {
+    if experiment.seats.is_empty() {
+        experiment.seats = default.iter().map(|s| s.parse().unwrap()).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use midas::Rules;
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        table: TableArgs,
+    }
+
+    fn try_apply(args: &[&str], rules: Rules) -> Result<Rules, String> {
+        let cli = Cli::try_parse_from([&["midas"], args].concat()).unwrap();
+        let mut experiment = Experiment {
+            rules,
+            ..Experiment::default()
+        };
+        cli.table.apply(&mut experiment)?;
+        Ok(experiment.rules)
+    }
+
+    fn apply(args: &[&str], rules: Rules) -> Rules {
+        try_apply(args, rules).unwrap()
+    }
+
+    #[test]
+    fn rule_flags() {
+        let file = Rules {
+            dealer_hits_soft_17: true,
+            dealer_peeks: true,
+            surrender: true,
+            double_after_split: false,
+            ..Rules::default()
+        };
+        // Rules from the file are kept unless a flag is given
+        assert_eq!(apply(&[], file), file);
+
+        let rules = apply(&["--no-h17", "--no-peek", "--no-surrender", "--das"], file);
+        assert!(!rules.dealer_hits_soft_17);
+        assert!(!rules.dealer_peeks);
+        assert!(!rules.surrender);
+        assert!(rules.double_after_split);
+
+        // The last of a pair wins
+        let rules = apply(
+            &["--h17", "--no-h17", "--no-das", "--das"],
+            Rules::default(),
+        );
+        assert!(!rules.dealer_hits_soft_17);
+        assert!(rules.double_after_split);
+        let rules = apply(&["--no-h17", "--h17"], Rules::default());
+        assert!(rules.dealer_hits_soft_17);
+    }
+    #[test]
+    fn invalid_rules() {
+        for args in [
+            &["--penetration", "0"][..],
+            &["--penetration", "1.5"],
+            &["--payout", "0"],
+            &["--seats", "0"],
+            &["--decks", "1", "--burn", "52"],
+            &["--min-bet", "50", "--max-bet", "10"],
+        ] {
+            assert!(try_apply(args, Rules::default()).is_err(), "{args:?}");
+        }
+        assert!(try_apply(&["--decks", "2", "--burn", "52"], Rules::default()).is_ok());
+    }
+}
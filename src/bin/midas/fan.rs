@@ -0,0 +1,94 @@
+use std::borrow::{Borrow, BorrowMut};
+use std::error::Error;
+use std::path::PathBuf;
+
+use minifb::{Key, Window, WindowOptions};
+use plotters::prelude::*;
+use plotters_bitmap::BitMapBackend;
+use plotters_bitmap::bitmap_pixel::BGRXPixel;
+
+use crate::buffer_wrapper::BufferWrapper;
+use midas::charts::{FanChart, Figure};
+use midas::{BankrollBands, Experiment, Registry, Session, StopCondition};
+
+use crate::config::default_seats;
+use crate::plot::DEFAULT_SEATS;
+
+const WIDTH: usize = 1000;
+const HEIGHT: usize = 750;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Number of sessions to play for each player
+    #[arg(long, default_value_t = 1000)]
+    sessions: usize,
+    /// Rounds in each session, the horizon of the chart
+    #[arg(long, default_value_t = 10_000)]
+    rounds: usize,
+    /// Number of points along each band. More points show more detail but take more memory
+    #[arg(long, default_value_t = 200)]
+    points: usize,
+    /// Save the chart to this path instead of showing it, as an SVG if it ends in `.svg` or a
+    /// PNG otherwise
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+pub fn run(
+    mut experiment: Experiment,
+    registry: &Registry,
+    args: &Args,
+) -> Result<(), Box<dyn Error>> {
+    default_seats(&mut experiment, DEFAULT_SEATS);
+    experiment.run.sessions = args.sessions;
+    let names: Vec<_> = experiment
+        .players(registry)?
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+
+    let interval = (args.rounds / args.points.max(1)).max(1);
+    let mut session = Session::new(vec![StopCondition::Rounds(args.rounds)]);
+    session.balance_interval = Some(interval);
+    let sessions = experiment
+        .simulation()
+        .run_sessions(|| experiment.players(registry).unwrap(), &session);
+
+    let bands: Vec<_> = (0..names.len())
+        .map(|id| BankrollBands::new(&sessions, id as u8, interval))
+        .collect();
+    for (name, bands) in names.iter().zip(&bands) {
+        let last = bands.percentiles.last().copied().unwrap_or_default();
+        println!(
+            "{name}: median ${:.0}, 5th to 95th percentile ${:.0} to ${:.0}, {:.1}% ruined",
+            last[2],
+            last[0],
+            last[4],
+            bands.ruin.probability() * 100.0
+        );
+    }
+    let chart = FanChart {
+        series: names.iter().map(String::as_str).zip(&bands).collect(),
+    };
+
+    if let Some(path) = &args.output {
+        chart.save(path, (WIDTH as u32, HEIGHT as u32))?;
+        println!("Chart saved to {}", path.display());
+        return Ok(());
+    }
+
+    let mut buf = BufferWrapper::new(vec![0u32; WIDTH * HEIGHT]);
+    {
+        let root = BitMapBackend::<BGRXPixel>::with_buffer_and_format(
+            buf.borrow_mut(),
+            (WIDTH as u32, HEIGHT as u32),
+        )?
+        .into_drawing_area();
+        chart.draw(&root)?;
+    }
+    let mut window = Window::new("Midas", WIDTH, HEIGHT, WindowOptions::default())?;
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        window.update_with_buffer(buf.borrow(), WIDTH, HEIGHT)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use midas::{Experiment, Registry};
+
+use crate::config::TableArgs;
+
+mod analyze;
+mod buffer_wrapper;
+mod chart;
+mod config;
+mod fan;
+mod play;
+mod plot;
+mod replay;
+mod simulate;
+mod table;
+
+/// Blackjack simulator
+#[derive(Parser)]
+#[command(name = "midas")]
+struct Cli {
+    /// Experiment file. Any flags given override its settings
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    #[command(flatten)]
+    table: TableArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run many sessions in parallel and save the results
+    Simulate(simulate::Args),
+    /// Plot each player's balance live as they play
+    Plot(plot::Args),
+    /// Chart the spread of each player's bankroll over many sessions
+    Fan(fan::Args),
+    /// Play at the table yourself, alongside any other seats
+    Play(play::Args),
+    /// Show the expected value of every action for a hand
+    Analyze(analyze::Args),
+    /// Print a basic strategy chart
+    Chart(chart::Args),
+    /// Replay a session from its recorded events, reporting any differences
+    Replay(replay::Args),
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+
+    let mut experiment = match &cli.config {
+        Some(path) => Experiment::load(path)?,
+        None => Experiment::default(),
+    };
+    cli.table.apply(&mut experiment)?;
+    let registry = Registry::default();
+
+    match cli.command {
+        Command::Simulate(args) => simulate::run(experiment, &registry, &args),
+        Command::Plot(args) => plot::run(experiment, &registry, &args),
+        Command::Fan(args) => fan::run(experiment, &registry, &args),
+        Command::Play(args) => play::run(experiment, &registry, &args),
+        Command::Analyze(args) => analyze::run(&experiment, &args),
+        Command::Chart(args) => chart::run(&experiment, &args),
+        Command::Replay(args) => replay::run(&args),
+    }
+}
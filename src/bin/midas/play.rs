@@ -0,0 +1,241 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use midas::events::Event;
+use midas::trainer::{Reference, TRUE_COUNT_TOLERANCE, Trainer};
+use midas::{
+    Action, ActionStrategy, AvailableActions, Card, CountingSystem, Experiment, Hand,
+    HumanActionStrategy, Player, Registry, Seat,
+};
+
+use crate::config::default_seats;
+use crate::table::LiveTable;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Grade every decision against a reference strategy and ask for the count between rounds
+    #[arg(long)]
+    train: bool,
+    /// Strategy decisions are graded against when training
+    #[arg(long, value_enum, default_value_t = ReferenceArg::Basic)]
+    reference: ReferenceArg,
+    /// Counting system drilled when training
+    #[arg(long, default_value = "Hi-Lo")]
+    system: String,
+    /// Chance of being asked for the count after each round when training
+    #[arg(long, default_value_t = 0.25, value_parser = parse_chance)]
+    drills: f64,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ReferenceArg {
+    /// Basic strategy, the best play against a full shoe
+    Basic,
+    /// The best play for the cards seen since the shuffle, including count deviations
+    Count,
+}
+
+/// Seats making decisions or bets as a human share the table with the other seats, whose
+/// cards and decisions are shown as they are played
+pub fn run(
+    mut experiment: Experiment,
+    registry: &Registry,
+    args: &Args,
+) -> Result<(), Box<dyn Error>> {
+    default_seats(&mut experiment, &["human:human:100"]);
+    let rules = experiment.rules;
+    let trainer = match args.train {
+        true => {
+            let system = CountingSystem::by_name(&args.system)
+                .ok_or_else(|| format!("unknown counting system `{}`", args.system))?;
+            let reference = match args.reference {
+                ReferenceArg::Basic => Reference::Basic,
+                ReferenceArg::Count => Reference::Count,
+            };
+            Some(Rc::new(RefCell::new(Trainer::new(
+                rules, reference, system,
+            ))))
+        }
+        false => None,
+    };
+
+    let mut names = vec![];
+    let mut humans = vec![];
+    let mut players = vec![];
+    for (id, seat) in experiment.seats.iter().enumerate() {
+        if seat.action.name == "human" && seat.betting.name != "human" {
+            return Err(format!(
+                "seat {} plays its hands as a human but bets with `{}`, use `human:human:{}` to \
+                 choose its bets too",
+                id + 1,
+                seat.betting.name,
+                seat.bankroll
+            )
+            .into());
+        }
+        let action: Box<dyn ActionStrategy> = match &trainer {
+            Some(trainer) if seat.action.name == "human" => Box::new(Coached(trainer.clone())),
+            _ => registry.action(&seat.action, &rules)?,
+        };
+        let betting = registry.betting(&seat.betting, &rules)?;
+        let name = match &seat.name {
+            Some(name) => name.clone(),
+            None if human(seat) => format!("Seat {}", id + 1),
+            None => seat.name(),
+        };
+        if human(seat) {
+            humans.push(id);
+        }
+        let mut player = Player::new(id as u8, seat.bankroll, action, betting);
+        player.name = name.clone();
+        names.push(name);
+        players.push(player);
+    }
+    if humans.is_empty() {
+        return Err("no seat is played by a human, use `--player human:human:100`".into());
+    }
+
+    let deciding = experiment
+        .seats
+        .iter()
+        .enumerate()
+        .filter(|(_, seat)| seat.action.name == "human")
+        .map(|(id, _)| id as u8)
+        .collect();
+    let live = Rc::new(RefCell::new(LiveTable::new(names, deciding)));
+    let mut game = experiment.game(players);
+    let observer = (live.clone(), trainer.clone());
+    game.observe(move |event| {
+        let (live, trainer) = &observer;
+        live.borrow_mut().apply(event);
+        if let Some(trainer) = trainer {
+            let mut trainer = trainer.borrow_mut();
+            match *event {
+                Event::Shuffle => trainer.shuffled(),
+                Event::Deal {
+                    card,
+                    face_up: true,
+                    ..
+                }
+                | Event::Reveal { card }
+                | Event::DealerDraw { card } => trainer.card_dealt(&card),
+                _ => {}
+            }
+        }
+    });
+
+    let limits = if rules.max_bet < f32::MAX {
+        format!("${} to ${}", rules.min_bet, rules.max_bet)
+    } else {
+        format!("${} or more", rules.min_bet)
+    };
+    println!("Bets are {limits}");
+    // Play until every human has left the table or can't make the minimum bet
+    while humans.iter().any(|&id| {
+        let player = &game.players[id];
+        player.seated && player.balance >= rules.min_bet
+    }) {
+        game.round();
+        live.borrow().print_results();
+        println!();
+
+        if let Some(trainer) = &trainer
+            && rand::random_bool(args.drills)
+            && !drill(&mut trainer.borrow_mut())
+        {
+            break;
+        }
+    }
+
+    for &id in &humans {
+        let player = &game.players[id];
+        println!(
+            "{} finished with ${} ({:+}) after {} rounds",
+            player.name,
+            player.balance,
+            player.balance - experiment.seats[id].bankroll,
+            game.rounds_played()
+        );
+    }
+    if let Some(trainer) = &trainer {
+        println!();
+        print!("{}", trainer.borrow());
+    }
+    Ok(())
+}
+
+/// Ask for the running and true counts, returning false once there's no more input
+fn drill(trainer: &mut Trainer) -> bool {
+    let system = trainer.system().name;
+    let Some(input) = read_line(&format!("{system} running count? ")) else {
+        return false;
+    };
+    match input.parse() {
+        Ok(count) if trainer.check_running_count(count) => println!("Right"),
+        Ok(_) => println!("Wrong, it's {:+}", trainer.running_count()),
+        Err(_) => println!("Skipped"),
+    }
+
+    let Some(input) = read_line("True count? ") else {
+        return false;
+    };
+    match input.parse() {
+        Ok(count) if trainer.check_true_count(count) => {
+            println!("Right, it's {:+.1}", trainer.true_count())
+        }
+        Ok(_) => println!(
+            "Wrong, it's {:+.1} and estimates within {TRUE_COUNT_TOLERANCE} count",
+            trainer.true_count()
+        ),
+        Err(_) => println!("Skipped"),
+    }
+    println!();
+    true
+}
+
+fn parse_chance(s: &str) -> Result<f64, String> {
+    match s.parse() {
+        Ok(chance) if (0.0..=1.0).contains(&chance) => Ok(chance),
+        _ => Err(format!("`{s}` isn't a chance from 0 to 1")),
+    }
+}
+
+/// Print `prompt` and read a trimmed line, or `None` once there's no more input
+fn read_line(prompt: &str) -> Option<String> {
+    print!("{prompt}");
+    io::stdout().flush().ok();
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => {
+            println!();
+            None
+        }
+        Ok(_) => Some(input.trim().to_lowercase()),
+    }
+}
+
+/// Asks for each decision, then compares it with the trainer's reference strategy
+struct Coached(Rc<RefCell<Trainer>>);
+
+impl ActionStrategy for Coached {
+    fn action(&self, hand: &Hand, dealer_card: &Card, available: &AvailableActions) -> Action {
+        let action = HumanActionStrategy.action(hand, dealer_card, available);
+        let grade = self
+            .0
+            .borrow_mut()
+            .grade(hand, dealer_card, available, action);
+        if !grade.correct() {
+            println!(
+                "Mistake: {:?} was the best play, {action:?} gives up {:.3} bets",
+                grade.best, grade.cost
+            );
+        }
+        action
+    }
+}
+
+fn human(seat: &Seat) -> bool {
+    seat.action.name == "human" || seat.betting.name == "human"
+}
@@ -0,0 +1,245 @@
+use std::borrow::{Borrow, BorrowMut};
+use std::error::Error;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
+use plotters::prelude::*;
+use plotters_bitmap::BitMapBackend;
+use plotters_bitmap::bitmap_pixel::BGRXPixel;
+
+use crate::buffer_wrapper::BufferWrapper;
+use midas::charts::{BalanceChart, Figure, TimeSeries};
+use midas::{Experiment, Game, Registry};
+
+use crate::config::default_seats;
+
+const WIDTH: usize = 800;
+const HEIGHT: usize = 600;
+/// Buckets kept for each player's balance, a few for every pixel across the chart
+const POINTS: usize = 2 * WIDTH;
+
+/// Players plotted when the experiment doesn't have any
+pub const DEFAULT_SEATS: &[&str] = &[
+    "simple:flat:10000",
+    "simple:hilo:10000",
+    "optimal:flat:10000",
+    "optimal:hilo:10000",
+];
+
+const CONTROLS: &str = "\
+Space      pause or resume
+Up, Down   play twice or half as many rounds between updates
+S          switch between expanding and scrolling
+[, ]       zoom in or out while scrolling
+L          switch between a linear and a logarithmic balance axis
+1 to 9     show or hide a player
+Escape     quit";
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// How many rounds to run before re-drawing chart.
+    /// Lower values will cause the chart to update more smoothly,
+    /// while higher values will allow more rounds to be run per second
+    #[arg(long, default_value_t = 100)]
+    rounds_per_update: usize,
+    /// Scroll horizontally once the chart reaches `max_rounds`, instead of expanding forever.
+    /// Expanding shows all historical data, downsampled so it draws just as quickly however
+    /// long the simulation runs
+    #[arg(long)]
+    scrolling: bool,
+    /// Maximum number of rounds shown when chart is scrolling
+    #[arg(long, default_value_t = 100_000)]
+    max_rounds: usize,
+    /// Show balances on a logarithmic axis
+    #[arg(long)]
+    log: bool,
+    /// Play `max_rounds` rounds without a window and save the chart to this path instead, as an
+    /// SVG if it ends in `.svg` or a PNG otherwise
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+pub fn run(
+    mut experiment: Experiment,
+    registry: &Registry,
+    args: &Args,
+) -> Result<(), Box<dyn Error>> {
+    default_seats(&mut experiment, DEFAULT_SEATS);
+    let mut game = experiment.game(experiment.players(registry)?);
+    let names: Vec<_> = game.players.iter().map(|p| p.name.clone()).collect();
+    let mut view = View {
+        paused: false,
+        rounds_per_update: args.rounds_per_update.max(1),
+        expanding: !args.scrolling,
+        max_rounds: args.max_rounds.max(1),
+        hidden: vec![],
+        log_scale: args.log,
+    };
+
+    let mut balances = Balances::new(game.players.len());
+
+    if let Some(path) = &args.output {
+        balances.play(&mut game, view.max_rounds, view.max_rounds);
+        view.expanding = true;
+        view.chart(&names, &balances)
+            .save(path, (WIDTH as u32, HEIGHT as u32))?;
+        println!(
+            "Chart of {} rounds saved to {}",
+            balances.rounds,
+            path.display()
+        );
+        return Ok(());
+    }
+
+    println!("{CONTROLS}");
+    let mut buf = BufferWrapper::new(vec![0u32; WIDTH * HEIGHT]);
+    let mut window = Window::new("Midas", WIDTH, HEIGHT, WindowOptions::default())?;
+    let mut changed = true;
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        for key in window.get_keys_pressed(KeyRepeat::No) {
+            changed |= view.press(key, names.len());
+        }
+        if !view.paused {
+            balances.play(&mut game, view.rounds_per_update, view.max_rounds);
+        } else if !changed {
+            window.update();
+            thread::sleep(Duration::from_millis(15));
+            continue;
+        }
+        changed = false;
+
+        let root = BitMapBackend::<BGRXPixel>::with_buffer_and_format(
+            buf.borrow_mut(),
+            (WIDTH as u32, HEIGHT as u32),
+        )?
+        .into_drawing_area();
+        view.chart(&names, &balances).draw(&root)?;
+        drop(root);
+        window.set_title(&view.title(balances.rounds));
+        window.update_with_buffer(buf.borrow(), WIDTH, HEIGHT)?;
+    }
+    Ok(())
+}
+
+/// Each player's balance after every round played so far
+struct Balances {
+    rounds: usize,
+    /// Every round, shown while expanding
+    history: Vec<TimeSeries>,
+    /// Only the rounds that fit while scrolling, so they stay at full detail
+    recent: Vec<TimeSeries>,
+}
+
+impl Balances {
+    fn new(players: usize) -> Self {
+        Self {
+            rounds: 0,
+            history: vec![TimeSeries::new(POINTS); players],
+            recent: vec![TimeSeries::new(POINTS); players],
+        }
+    }
+
+    /// Play `rounds` more rounds while anyone has money left, keeping the last `max_rounds`
+    /// for scrolling
+    fn play(&mut self, game: &mut Game, rounds: usize, max_rounds: usize) {
+        for _ in 0..rounds {
+            if game.players.iter().all(|p| p.balance <= 0.0) {
+                break;
+            }
+            let _ = game.round();
+            self.rounds += 1;
+            for (i, player) in game.players.iter().enumerate() {
+                if player.balance > 0.0 {
+                    self.history[i].push(self.rounds, player.balance);
+                    self.recent[i].push(self.rounds, player.balance);
+                }
+                self.recent[i].drop_before(self.rounds.saturating_sub(max_rounds));
+            }
+        }
+    }
+}
+
+/// What the plot window is showing, changed from the keyboard
+struct View {
+    paused: bool,
+    rounds_per_update: usize,
+    expanding: bool,
+    /// Rounds shown while scrolling
+    max_rounds: usize,
+    hidden: Vec<usize>,
+    log_scale: bool,
+}
+
+impl View {
+    /// Respond to a key press, returning whether the chart needs to be redrawn
+    fn press(&mut self, key: Key, players: usize) -> bool {
+        const SEATS: [Key; 9] = [
+            Key::Key1,
+            Key::Key2,
+            Key::Key3,
+            Key::Key4,
+            Key::Key5,
+            Key::Key6,
+            Key::Key7,
+            Key::Key8,
+            Key::Key9,
+        ];
+        match key {
+            Key::Space => self.paused = !self.paused,
+            Key::Up => self.rounds_per_update = self.rounds_per_update.saturating_mul(2),
+            Key::Down => self.rounds_per_update = (self.rounds_per_update / 2).max(1),
+            Key::S => self.expanding = !self.expanding,
+            Key::LeftBracket => self.max_rounds = (self.max_rounds / 2).max(100),
+            Key::RightBracket => self.max_rounds = self.max_rounds.saturating_mul(2),
+            Key::L => self.log_scale = !self.log_scale,
+            _ => match SEATS.iter().position(|&seat| seat == key) {
+                Some(seat) if seat < players => {
+                    match self.hidden.iter().position(|&hidden| hidden == seat) {
+                        Some(i) => {
+                            self.hidden.remove(i);
+                        }
+                        None => self.hidden.push(seat),
+                    }
+                }
+                _ => return false,
+            },
+        }
+        true
+    }
+
+    fn chart<'a>(&self, names: &'a [String], balances: &'a Balances) -> BalanceChart<'a> {
+        let rounds = balances.rounds;
+        let (data, first_round, last_round) = if self.expanding {
+            (&balances.history, 0, rounds)
+        } else {
+            (
+                &balances.recent,
+                rounds.saturating_sub(self.max_rounds),
+                rounds.max(self.max_rounds),
+            )
+        };
+        BalanceChart {
+            series: names.iter().map(String::as_str).zip(data).collect(),
+            first_round,
+            last_round,
+            hidden: self.hidden.clone(),
+            log_scale: self.log_scale,
+        }
+    }
+
+    fn title(&self, n_rounds: usize) -> String {
+        format!(
+            "Midas - round {n_rounds}{}, {} rounds per update, {}, {} axis",
+            if self.paused { " (paused)" } else { "" },
+            self.rounds_per_update,
+            if self.expanding {
+                "expanding".to_string()
+            } else {
+                format!("last {} rounds", self.max_rounds)
+            },
+            if self.log_scale { "log" } else { "linear" },
+        )
+    }
+}
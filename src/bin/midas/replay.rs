@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use midas::Replay;
+use midas::events::load_events;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Events saved by `simulate --events`, replayed at the table they were recorded at
+    events: PathBuf,
+    /// Index of the session to replay
+    #[arg(long, default_value_t = 0)]
+    session: usize,
+    /// Print every event of each round as it is replayed
+    #[arg(long)]
+    verbose: bool,
+}
+
+pub fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let (header, sessions) = load_events(&args.events)?;
+    let events = sessions
+        .get(args.session)
+        .ok_or_else(|| format!("no session {} in {}", args.session, args.events.display()))?;
+
+    let mut replay = Replay::new(&header, events);
+    let mut rounds = 0;
+    let mut diverged = 0;
+    while let Some(round) = replay.step() {
+        rounds += 1;
+        if args.verbose {
+            println!("Round {}", round.round);
+            round
+                .replayed
+                .iter()
+                .for_each(|event| println!("  {event:?}"));
+        }
+        if let Some(divergence) = round.divergence() {
+            diverged += 1;
+            println!(
+                "Round {} diverged at event {}",
+                divergence.round, divergence.index
+            );
+            println!("  Recorded: {:?}", divergence.recorded);
+            println!("  Replayed: {:?}", divergence.replayed);
+        }
+    }
+
+    println!("Replayed {rounds} rounds, {diverged} diverged from the recording");
+    if diverged > 0 {
+        return Err("replay diverged from the recording".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use midas::analysis::{CountEfficiency, effects_of_removal};
+use midas::charts::{BankrollHistogram, CountChart, Figure};
+use midas::events::{EventWriter, Header};
+use midas::export::{HandWriter, save_sessions};
+use midas::{
+    CountStatistics, CountingSystem, Experiment, Registry, SimulatedRuin, Statistics,
+    required_bankroll, risk_of_ruin, risk_of_ruin_within,
+};
+
+use crate::config::default_seats;
+
+const CHART_SIZE: (u32, u32) = (800, 600);
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Number of sessions to play
+    #[arg(long)]
+    sessions: Option<usize>,
+    /// Number of threads to run sessions on, defaults to every core
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Path to save each session's results to
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Path to save every hand to, which can be very large for long runs
+    #[arg(long)]
+    hands: Option<PathBuf>,
+    /// Path to save every event at the table to as JSON Lines, which is larger still
+    #[arg(long)]
+    events: Option<PathBuf>,
+    /// Directory to save a histogram of final bankrolls and a chart of EV by true count to for
+    /// each player
+    #[arg(long)]
+    charts: Option<PathBuf>,
+    /// Save charts as SVG instead of PNG
+    #[arg(long)]
+    svg: bool,
+    /// Stop a session after this many rounds
+    #[arg(long)]
+    max_rounds: Option<usize>,
+    /// Stop a session once every player reaches this balance
+    #[arg(long)]
+    goal: Option<f32>,
+}
+
+pub fn run(
+    mut experiment: Experiment,
+    registry: &Registry,
+    args: &Args,
+) -> Result<(), Box<dyn Error>> {
+    default_seats(&mut experiment, &["optimal:flat:100"]);
+    let run = &mut experiment.run;
+    if let Some(sessions) = args.sessions {
+        run.sessions = sessions;
+    }
+    if args.threads.is_some() {
+        run.threads = args.threads;
+    }
+    if args.output.is_some() {
+        run.output = args.output.clone();
+    }
+    if args.hands.is_some() {
+        run.hands_output = args.hands.clone();
+    }
+    if args.events.is_some() {
+        run.events_output = args.events.clone();
+    }
+    if args.max_rounds.is_some() {
+        run.max_rounds = args.max_rounds;
+    }
+    if args.goal.is_some() {
+        run.goal = args.goal;
+    }
+
+    // Check every strategy can be created before starting
+    let systems: Vec<_> = experiment
+        .players(registry)?
+        .iter()
+        .map(|player| player.counting_system())
+        .collect();
+    let simulation = experiment.simulation();
+    let session = experiment.run.session();
+    // Hands and events are written as they are played, shared between the threads running
+    // sessions
+    let hands = match &experiment.run.hands_output {
+        Some(path) => Some(Mutex::new(HandWriter::create(path)?)),
+        None => None,
+    };
+    let events = match &experiment.run.events_output {
+        Some(path) => {
+            let header = Header {
+                rules: experiment.rules,
+                seats: experiment.seats.clone(),
+            };
+            Some(Mutex::new(EventWriter::create(path, &header)?))
+        }
+        None => None,
+    };
+    let sessions = simulation
+        .run(
+            || experiment.players(registry).unwrap(),
+            |index, game| {
+                session.play_with(game, |game| {
+                    if let Some(writer) = &hands {
+                        writer.lock().unwrap().write(index, game.hand_records())?;
+                    }
+                    if let Some(writer) = &events {
+                        writer.lock().unwrap().write(index, &game.take_events())?;
+                    }
+                    Ok(())
+                })
+            },
+        )
+        .into_iter()
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let output = experiment
+        .run
+        .output
+        .as_deref()
+        .unwrap_or(Path::new("results.csv"));
+    let effects = systems
+        .iter()
+        .any(Option::is_some)
+        .then(|| effects_of_removal(&experiment.rules));
+    for (id, seat) in experiment.seats.iter().enumerate() {
+        let mut statistics = Statistics::default();
+        let mut by_count = CountStatistics::default();
+        for session in &sessions {
+            statistics.merge(&session.players[id].statistics);
+            by_count.merge(&session.players[id].by_count);
+        }
+        let summary = statistics.summary(experiment.run.hands_per_hour);
+        let name = seat.name();
+        println!("Player {id} ({name})");
+        println!("{summary}");
+
+        let (ev, sd) = (summary.ev_per_hand, summary.sd_per_hand);
+        let bankroll = seat.bankroll as f64;
+        let ruin = SimulatedRuin::new(&sessions, id as u8);
+        print!(
+            "Simulated RoR       {:.2}% of sessions",
+            ruin.probability() * 100.0
+        );
+        match (ruin.mean_rounds_to_ruin(), ruin.median_rounds_to_ruin()) {
+            (Some(mean), Some(median)) => {
+                println!(", after {mean:.0} rounds on average ({median} median)")
+            }
+            _ => println!(),
+        }
+        if let Some(rounds) = experiment.run.max_rounds {
+            println!(
+                "Analytic RoR        {:.2}% within {rounds} rounds",
+                risk_of_ruin_within(ev, sd, bankroll, rounds as f64) * 100.0
+            );
+        }
+        println!(
+            "                    {:.2}% playing forever",
+            risk_of_ruin(ev, sd, bankroll) * 100.0
+        );
+        let target = experiment.run.target_ror;
+        match required_bankroll(ev, sd, target) {
+            Some(required) => println!("Bankroll for {}% RoR {required:.2}", target * 100.0),
+            None => println!(
+                "Bankroll for {}% RoR unlimited without an edge",
+                target * 100.0
+            ),
+        }
+        if let (Some(system), Some(effects)) = (&systems[id], &effects) {
+            let efficiency = CountEfficiency::with_effects(system, &experiment.rules, effects);
+            println!(
+                "BC / PE             {:.3} / {:.3} for {}",
+                efficiency.betting_correlation, efficiency.playing_efficiency, system.name
+            );
+        }
+        println!();
+        let system = systems[id].unwrap_or(CountingSystem::HI_LO);
+        println!("Results by {} true count", system.name);
+        println!("{by_count}");
+
+        if let Some(dir) = &args.charts {
+            let extension = if args.svg { "svg" } else { "png" };
+            let bankrolls: Vec<_> = sessions.iter().map(|s| s.players[id].balance).collect();
+            fs::create_dir_all(dir)?;
+            let histogram = BankrollHistogram {
+                title: &format!("Final bankrolls of {name}"),
+                bankrolls: &bankrolls,
+                bins: 40,
+            };
+            histogram.save(dir.join(format!("bankrolls-{id}.{extension}")), CHART_SIZE)?;
+            let chart = CountChart {
+                title: &format!("EV by {} true count of {name}", system.name),
+                by_count: &by_count,
+            };
+            chart.save(dir.join(format!("counts-{id}.{extension}")), CHART_SIZE)?;
+        }
+    }
+
+    let rounds: usize = sessions.iter().map(|s| s.rounds).sum();
+    save_sessions(output, &sessions)?;
+    println!(
+        "Average of {} rounds. Results saved to {}",
+        rounds / sessions.len().max(1),
+        output.display()
+    );
+    if let (Some(path), Some(hands)) = (&experiment.run.hands_output, hands) {
+        hands.into_inner().unwrap().flush()?;
+        println!("Hands saved to {}", path.display());
+    }
+    if let (Some(path), Some(events)) = (&experiment.run.events_output, events) {
+        events.into_inner().unwrap().flush()?;
+        println!("Events saved to {}", path.display());
+    }
+    if let Some(dir) = &args.charts {
+        println!("Charts saved to {}", dir.display());
+    }
+    Ok(())
+}
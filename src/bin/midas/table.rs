@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use midas::events::{Event, Recipient};
+use midas::{Action, Card, Hand, RoundResult};
+
+/// What can be seen at the table during a round, built up from its events
+#[derive(Default)]
+pub struct Table {
+    dealer: Vec<Card>,
+    hole_card_shown: bool,
+    /// Cards of each hand, by player and hand index
+    hands: BTreeMap<(u8, usize), Vec<Card>>,
+    /// Card taken from a hand that was just split, waiting for the new hand to be dealt
+    split_card: Option<Card>,
+    settled: Vec<(u8, usize, RoundResult, f32)>,
+    balances: Vec<(u8, f32)>,
+}
+
+impl Table {
+    pub fn apply(&mut self, event: &Event) {
+        match *event {
+            Event::Round { .. } => *self = Self::default(),
+            Event::Deal {
+                card,
+                to: Recipient::Dealer,
+                ..
+            }
+            | Event::DealerDraw { card } => self.dealer.push(card),
+            Event::Deal {
+                card,
+                to: Recipient::Player { player, hand },
+                ..
+            } => {
+                let cards = self
+                    .hands
+                    .entry((player, hand))
+                    .or_insert_with(|| self.split_card.take().into_iter().collect());
+                cards.push(card);
+            }
+            Event::Decision {
+                player,
+                hand,
+                action: Action::Split,
+                ..
+            } => {
+                self.split_card = self
+                    .hands
+                    .get_mut(&(player, hand))
+                    .and_then(|cards| cards.pop());
+            }
+            Event::Reveal { .. } => self.hole_card_shown = true,
+            Event::Settle {
+                player,
+                hand,
+                result,
+                net,
+            } => self.settled.push((player, hand, result, net)),
+            Event::Balance { player, balance } => self.balances.push((player, balance)),
+            Event::Shuffle | Event::Burn { .. } | Event::Bet { .. } | Event::Decision { .. } => {}
+        }
+    }
+
+    fn hand(&self, player: u8, hand: usize) -> &[Card] {
+        self.hands
+            .get(&(player, hand))
+            .map_or(&[][..], Vec::as_slice)
+    }
+
+    /// Print the dealer's hand, then each hand's result and every player's balance
+    pub fn print_results(&self, names: &[String]) {
+        print!("{}", self.results(names));
+    }
+
+    fn results(&self, names: &[String]) -> String {
+        let dealer = if self.hole_card_shown {
+            describe(&self.dealer)
+        } else {
+            describe(&self.dealer[..self.dealer.len().min(1)])
+        };
+        let width = width(names);
+        let mut results = String::new();
+        writeln!(results, "{:<width$}  {dealer}", "Dealer").unwrap();
+        for &(player, hand, result, net) in &self.settled {
+            let cards = self.hand(player, hand);
+            writeln!(
+                results,
+                "{:<width$}  {:<24} {:<10} {net:+}",
+                names[player as usize],
+                describe(cards),
+                format!("{result:?}")
+            )
+            .unwrap();
+        }
+        for &(player, balance) in &self.balances {
+            writeln!(
+                results,
+                "{:<width$}  balance ${balance}",
+                names[player as usize]
+            )
+            .unwrap();
+        }
+        results
+    }
+}
+
+/// Shows the table as a round is dealt, for a human sitting at it. Every hand is shown once
+/// the first cards are out, then the decisions and cards of the other seats as they happen.
+pub struct LiveTable {
+    table: Table,
+    names: Vec<String>,
+    /// Players typing their own decisions, who see their hand when asked for them
+    humans: Vec<u8>,
+    dealt: bool,
+}
+
+impl LiveTable {
+    pub fn new(names: Vec<String>, humans: Vec<u8>) -> Self {
+        Self {
+            table: Table::default(),
+            names,
+            humans,
+            dealt: false,
+        }
+    }
+
+    pub fn print_results(&self) {
+        self.table.print_results(&self.names);
+    }
+
+    pub fn apply(&mut self, event: &Event) {
+        self.table.apply(event);
+        let width = width(&self.names);
+        match *event {
+            Event::Round { round } => {
+                self.dealt = false;
+                println!("Round {round}");
+            }
+            Event::Shuffle => println!("The shoe is shuffled"),
+            // The hole card is the last card of the first deal
+            Event::Deal { face_up: false, .. } => {
+                self.dealt = true;
+                println!("{:<width$}  {}", "Dealer", self.table.dealer[0]);
+                for (&(player, _), cards) in &self.table.hands {
+                    let name = &self.names[player as usize];
+                    println!("{name:<width$}  {}", describe(cards));
+                }
+            }
+            Event::Deal {
+                to: Recipient::Player { player, hand },
+                ..
+            } if self.dealt && !self.humans.contains(&player) => {
+                let name = &self.names[player as usize];
+                println!(
+                    "{name:<width$}  {}",
+                    describe(self.table.hand(player, hand))
+                );
+            }
+            Event::Decision { player, action, .. } if !self.humans.contains(&player) => {
+                let name = &self.names[player as usize];
+                println!("{name:<width$}  {}s", format!("{action:?}").to_lowercase());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn width(names: &[String]) -> usize {
+    names.iter().map(String::len).max().unwrap_or(0).max(6)
+}
+
+/// Cards and their total, e.g. `AS 6D (soft 17)`
+pub fn describe(cards: &[Card]) -> String {
+    let hand = Hand {
+        cards: cards.to_vec(),
+    };
+    let soft = if hand.is_soft() && hand.value() < 21 {
+        "soft "
+    } else {
+        ""
+    };
+    format!("{hand} ({soft}{})", hand.value())
+}
+
+#[cfg(test)]
+mod tests {
+    use midas::{ActionStrategy, AvailableActions, FlatBettingStrategy, Game, Player, Rules};
+
+    use super::*;
+
+    /// Splits every pair it can and stands on everything else
+    struct Splitter;
+
+    impl ActionStrategy for Splitter {
+        fn action(
+            &self,
+            _hand: &Hand,
+            _dealer_card: &Card,
+            available: &AvailableActions,
+        ) -> Action {
+            match available.split {
+                true => Action::Split,
+                false => Action::Stand,
+            }
+        }
+    }
+
+    #[test]
+    fn split_round() {
+        let player = Player::new(0, 1000.0, Box::new(Splitter), Box::new(FlatBettingStrategy));
+        let mut game = Game::with_seed(vec![player], Rules::default(), 7);
+        game.record_events(true);
+        game.record_hands(true);
+        let events = loop {
+            game.round();
+            let events = game.take_events();
+            let split = events.iter().any(|event| {
+                matches!(
+                    event,
+                    Event::Decision {
+                        action: Action::Split,
+                        ..
+                    }
+                )
+            });
+            if split {
+                break events;
+            }
+            assert!(game.rounds_played() < 1000, "no pair was dealt");
+        };
+
+        let mut table = Table::default();
+        events.iter().for_each(|event| table.apply(event));
+        let records = game.hand_records();
+        assert!(records.len() >= 2);
+        assert_eq!(table.settled.len(), records.len());
+        assert_eq!(table.dealer, records[0].dealer);
+        for (&(player, hand, result, _), record) in table.settled.iter().zip(records) {
+            assert_eq!(table.hand(player, hand), record.cards);
+            assert_eq!(result, record.outcome.result);
+        }
+
+        let names = vec!["Splitter".to_string()];
+        let results = table.results(&names);
+        let lines: Vec<_> = results.lines().collect();
+        assert_eq!(lines.len(), 1 + records.len() + 1);
+        assert_eq!(
+            lines[0],
+            format!("Dealer    {}", describe(&records[0].dealer))
+        );
+        for (line, record) in lines[1..].iter().zip(records) {
+            assert!(line.starts_with("Splitter"));
+            assert!(line.contains(&describe(&record.cards)));
+            assert!(line.contains(&format!("{:?}", record.outcome.result)));
+            assert!(line.ends_with(&format!("{:+}", record.outcome.net)));
+        }
+        let balance = game.players[0].balance;
+        assert_eq!(
+            lines[lines.len() - 1],
+            format!("Splitter  balance ${balance}")
+        );
+    }
+}
@@ -1,43 +0,0 @@
-use std::collections::HashMap;
-
-use midas::{
-    FlatBettingStrategy, Game, Player, RoundResult, SimpleActionStrategy, save_results_to_csv,
-};
-
-fn main() {
-    let mut all_results = vec![];
-    let mut total_rounds = 0;
-    for _ in 0..100_000 {
-        let mut num_rounds = 0;
-        let mut players = Vec::new();
-        let simple_ai = Player::new(
-            0,
-            100.0,
-            Box::new(SimpleActionStrategy),
-            Box::new(FlatBettingStrategy),
-        );
-        players.push(simple_ai);
-        let mut game = Game::new(players);
-
-        let mut results = HashMap::new();
-        results.insert(RoundResult::Bust, 0);
-        results.insert(RoundResult::Lose, 0);
-        results.insert(RoundResult::Push, 0);
-        results.insert(RoundResult::Win, 0);
-        results.insert(RoundResult::Blackjack, 0);
-
-        while game.players[0].balance >= 10.0 {
-            let round_results = game.round();
-            for result in round_results {
-                *results.get_mut(&result).unwrap() += 1;
-                num_rounds += 1;
-            }
-        }
-
-        total_rounds += num_rounds;
-        all_results.push(results);
-    }
-
-    save_results_to_csv(all_results);
-    println!("Average of {} rounds.", total_rounds / 100_000);
-}
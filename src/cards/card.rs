@@ -1,6 +1,8 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Suit {
     Club,
     Diamond,
@@ -10,9 +12,19 @@ pub enum Suit {
 
 impl Suit {
     pub const VARIANTS: [Self; 4] = [Self::Club, Self::Diamond, Self::Heart, Self::Spade];
+
+    /// Single letter for the suit, e.g. `S` for spades
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Suit::Club => "C",
+            Suit::Diamond => "D",
+            Suit::Heart => "H",
+            Suit::Spade => "S",
+        }
+    }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Face {
     Ace,
     Two,
@@ -60,9 +72,28 @@ impl Face {
             Face::Ten | Face::Jack | Face::Queen | Face::King => 10,
         }
     }
+
+    /// Short name for the face as printed on a card, e.g. `A` or `10`
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Face::Ace => "A",
+            Face::Two => "2",
+            Face::Three => "3",
+            Face::Four => "4",
+            Face::Five => "5",
+            Face::Six => "6",
+            Face::Seven => "7",
+            Face::Eight => "8",
+            Face::Nine => "9",
+            Face::Ten => "10",
+            Face::Jack => "J",
+            Face::Queen => "Q",
+            Face::King => "K",
+        }
+    }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Card {
     pub suit: Suit,
     pub face: Face,
@@ -73,3 +104,10 @@ impl Debug for Card {
         write!(f, "{:?}", self.face)
     }
 }
+
+/// Face and suit, e.g. `10H` for the ten of hearts
+impl Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.face.symbol(), self.suit.symbol())
+    }
+}
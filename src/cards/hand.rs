@@ -83,7 +83,13 @@ impl Hand {
 
 impl Display for Hand {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.cards)
+        for (i, card) in self.cards.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{card}")?;
+        }
+        Ok(())
     }
 }
 
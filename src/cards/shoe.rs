@@ -1,51 +1,137 @@
+use std::collections::VecDeque;
+
+use rand::SeedableRng;
 use rand::prelude::SliceRandom;
-use rand::rng;
+use rand::rngs::StdRng;
 
 use super::card::{Card, Face, Suit};
 
 pub struct Shoe {
     pub cards: Vec<Card>,
     pub discards: Vec<Card>,
+    rng: StdRng,
+    /// Number of times the discards have been shuffled back in
+    reshuffles: usize,
+    stack: Option<Stack>,
+}
+
+/// When a stacked shoe is shuffled, counted in cards dealt from the start
+struct Stack {
+    shuffles: VecDeque<usize>,
+    dealt: usize,
 }
 
 impl Shoe {
-    pub fn new() -> Self {
-        let mut cards = Vec::new();
-        for suit in Suit::VARIANTS {
-            for face in Face::VARIANTS {
-                cards.push(Card { suit, face });
-            }
-        }
+    pub fn new(decks: u8) -> Self {
+        Self::with_rng(decks, StdRng::from_os_rng())
+    }
 
+    /// Shoe that is always shuffled into the same order for the same seed
+    pub fn seeded(decks: u8, seed: u64) -> Self {
+        Self::with_rng(decks, StdRng::seed_from_u64(seed))
+    }
+
+    /// Shoe that deals `cards` in order, shuffling only once each number of cards in
+    /// `shuffles` has been dealt. Cards are dealt in order from a single unshuffled deck once
+    /// they run out.
+    pub fn stacked(cards: Vec<Card>, shuffles: Vec<usize>) -> Self {
         Self {
-            cards,
+            cards: cards.into_iter().rev().collect(),
             discards: vec![],
+            rng: StdRng::seed_from_u64(0),
+            reshuffles: 0,
+            stack: Some(Stack {
+                shuffles: shuffles.into(),
+                dealt: 0,
+            }),
         }
     }
 
+    fn with_rng(decks: u8, rng: StdRng) -> Self {
+        let mut shoe = Self {
+            cards: unshuffled(decks),
+            discards: vec![],
+            rng,
+            reshuffles: 0,
+            stack: None,
+        };
+        shoe.shuffle();
+        shoe
+    }
+
     /// Returns dealt card and indicates whether or not the deal resulted in a shuffle
     pub fn deal(&mut self) -> (Card, bool) {
+        if let Some(stack) = &mut self.stack {
+            let shuffled = stack.shuffles.front() == Some(&stack.dealt);
+            stack.dealt += 1;
+            if shuffled {
+                self.reshuffle();
+            }
+            if self.cards.is_empty() {
+                self.cards = unshuffled(1).into_iter().rev().collect();
+            }
+            return (self.cards.pop().unwrap(), shuffled);
+        }
+
         if let Some(card) = self.cards.pop() {
             (card, false)
         } else {
-            self.shuffle();
+            self.reshuffle();
             (
-                self.cards.pop().expect(
-                    format!(
+                self.cards.pop().unwrap_or_else(|| {
+                    panic!(
                         "Shoe: {}, Discards: {}",
                         self.cards.len(),
                         self.discards.len()
                     )
-                    .as_str(),
-                ),
+                }),
                 true,
             )
         }
     }
 
+    /// Number of shoes that have been dealt out completely
+    pub fn reshuffles(&self) -> usize {
+        self.reshuffles
+    }
+
+    /// Whether the cut card has been reached, where `penetration` is the fraction of the shoe
+    /// dealt before reshuffling. An empty shoe is always past it, so it's reshuffled between
+    /// rounds rather than once the next round has started.
+    pub fn past_cut(&self, penetration: f32) -> bool {
+        if let Some(stack) = &self.stack {
+            return stack.shuffles.front() == Some(&stack.dealt);
+        }
+        let total = self.cards.len() + self.discards.len();
+        self.cards.is_empty() || (self.cards.len() as f32) < total as f32 * (1.0 - penetration)
+    }
+
+    /// Shuffle the discards back into the shoe
+    pub fn reshuffle(&mut self) {
+        if let Some(stack) = &mut self.stack {
+            stack.shuffles.pop_front();
+            self.discards.clear();
+        } else {
+            self.shuffle();
+        }
+        self.reshuffles += 1;
+    }
+
     fn shuffle(&mut self) {
         self.cards.append(&mut self.discards);
-        let mut rng = rng();
-        self.cards.shuffle(&mut rng);
+        self.cards.shuffle(&mut self.rng);
+    }
+}
+
+/// Every card in `decks` decks, in order
+fn unshuffled(decks: u8) -> Vec<Card> {
+    let mut cards = Vec::new();
+    for _ in 0..decks {
+        for suit in Suit::VARIANTS {
+            for face in Face::VARIANTS {
+                cards.push(Card { suit, face });
+            }
+        }
     }
+    cards
 }
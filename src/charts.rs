@@ -0,0 +1,517 @@
+//! Charts of simulation results, drawn onto any plotters backend or saved straight to PNG or SVG
+//! files without a window.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::path::Path;
+
+use plotters::coord::Shift;
+use plotters::coord::ranged1d::ValueFormatter;
+use plotters::coord::types::RangedCoordf32;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use plotters_bitmap::BitMapBackend;
+
+use crate::risk::BankrollBands;
+use crate::statistics::CountStatistics;
+
+/// A chart that can be drawn onto any drawing area, or saved to a file
+pub trait Figure {
+    fn draw<DB: DrawingBackend>(&self, area: &DrawingArea<DB, Shift>) -> Result<(), Box<dyn Error>>
+    where
+        DB::ErrorType: 'static;
+
+    /// Save the chart to `path` as an SVG if it ends in `.svg`, or a PNG otherwise
+    fn save(&self, path: impl AsRef<Path>, size: (u32, u32)) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|ext| ext == "svg") {
+            let area = SVGBackend::new(path, size).into_drawing_area();
+            self.draw(&area)?;
+            area.present()?;
+        } else {
+            let area = BitMapBackend::new(path, size).into_drawing_area();
+            self.draw(&area)?;
+            area.present()?;
+        }
+        Ok(())
+    }
+}
+
+/// Values over time kept in a bounded number of buckets, so long runs can be plotted in
+/// constant memory and drawing time.
+///
+/// Each bucket keeps the lowest, highest and last value of a run of consecutive points. Once
+/// there are more than `capacity` buckets, neighbouring buckets are merged and every bucket
+/// covers twice as many points as before, which keeps the extremes visible at any length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSeries {
+    buckets: VecDeque<Bucket>,
+    capacity: usize,
+    /// Points covered by each bucket
+    width: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bucket {
+    start: usize,
+    min: (usize, f32),
+    max: (usize, f32),
+    last: (usize, f32),
+}
+
+impl TimeSeries {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buckets: VecDeque::new(),
+            capacity: capacity.max(2),
+            width: 1,
+        }
+    }
+
+    /// Add `value` at `x`, which must be after every point already added
+    pub fn push(&mut self, x: usize, value: f32) {
+        let point = (x, value);
+        match self.buckets.back_mut() {
+            Some(bucket) if bucket.start / self.width == x / self.width => {
+                if value < bucket.min.1 {
+                    bucket.min = point;
+                }
+                if value > bucket.max.1 {
+                    bucket.max = point;
+                }
+                bucket.last = point;
+            }
+            _ => self.buckets.push_back(Bucket {
+                start: x,
+                min: point,
+                max: point,
+                last: point,
+            }),
+        }
+
+        while self.buckets.len() > self.capacity {
+            self.width *= 2;
+            let mut merged: VecDeque<Bucket> = VecDeque::with_capacity(self.capacity);
+            for bucket in self.buckets.drain(..) {
+                match merged.back_mut() {
+                    Some(previous) if previous.start / self.width == bucket.start / self.width => {
+                        if bucket.min.1 < previous.min.1 {
+                            previous.min = bucket.min;
+                        }
+                        if bucket.max.1 > previous.max.1 {
+                            previous.max = bucket.max;
+                        }
+                        previous.last = bucket.last;
+                    }
+                    _ => merged.push_back(bucket),
+                }
+            }
+            self.buckets = merged;
+        }
+    }
+
+    /// Forget every bucket that ends before `x`, for plots that scroll
+    pub fn drop_before(&mut self, x: usize) {
+        while self.buckets.front().is_some_and(|bucket| bucket.last.0 < x) {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Points to draw, in order: the lowest and highest values of each bucket and its last
+    pub fn points(&self) -> impl Iterator<Item = (usize, f32)> + '_ {
+        self.buckets.iter().flat_map(|bucket| {
+            let mut points = [bucket.min, bucket.max, bucket.last];
+            points.sort_by_key(|&(x, _)| x);
+            let [a, b, c] = points;
+            [
+                Some(a),
+                (b.0 != a.0).then_some(b),
+                (c.0 != b.0).then_some(c),
+            ]
+            .into_iter()
+            .flatten()
+        })
+    }
+
+    /// Highest value still kept
+    pub fn max(&self) -> Option<f32> {
+        self.buckets
+            .iter()
+            .map(|bucket| bucket.max.1)
+            .reduce(f32::max)
+    }
+}
+
+/// Each player's balance over time, between `first_round` and `last_round`
+pub struct BalanceChart<'a> {
+    pub series: Vec<(&'a str, &'a TimeSeries)>,
+    pub first_round: usize,
+    /// Last round shown, which may be past the end of the data to leave room for more
+    pub last_round: usize,
+    /// Indices of series that aren't drawn. The rest keep the colours they'd have with every
+    /// series shown.
+    pub hidden: Vec<usize>,
+    /// Show balances on a logarithmic axis, so that swings are shown relative to the balance
+    pub log_scale: bool,
+}
+
+impl BalanceChart<'_> {
+    fn draw_with<DB, Y>(&self, area: &DrawingArea<DB, Shift>, y: Y) -> Result<(), Box<dyn Error>>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+        Y: Ranged<ValueType = f32> + ValueFormatter<f32>,
+    {
+        area.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(area)
+            .margin(10)
+            .caption("Balance over time", ("sans-serif", 30))
+            .x_label_area_size(40)
+            .y_label_area_size(50)
+            .build_cartesian_2d(
+                self.first_round..self.last_round.max(self.first_round + 1),
+                y,
+            )?;
+
+        chart
+            .configure_mesh()
+            .x_label_formatter(&|&x| format!("{x}"))
+            .y_label_formatter(&|&y| format!("${y:.0}"))
+            .x_labels(15)
+            .y_labels(10)
+            .x_desc("Rounds")
+            .y_desc("Balance")
+            .axis_desc_style(("sans-serif", 15))
+            .draw()?;
+
+        for (i, &(name, balances)) in self.series.iter().enumerate() {
+            if self.hidden.contains(&i) {
+                continue;
+            }
+            chart
+                .draw_series(LineSeries::new(balances.points(), Palette99::pick(i)))?
+                .label(name)
+                .legend(move |(x, y)| {
+                    Rectangle::new([(x - 5, y - 5), (x + 5, y + 5)], Palette99::pick(i))
+                });
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+        Ok(())
+    }
+}
+
+impl Figure for BalanceChart<'_> {
+    fn draw<DB: DrawingBackend>(&self, area: &DrawingArea<DB, Shift>) -> Result<(), Box<dyn Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        let shown = || {
+            self.series
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !self.hidden.contains(i))
+                .map(|(_, &(_, balances))| balances)
+        };
+        let max_y = shown().filter_map(TimeSeries::max).fold(1.0, f32::max);
+
+        if self.log_scale {
+            // Balances can reach zero, so the axis starts from the lowest positive balance
+            let min_y = shown()
+                .flat_map(TimeSeries::points)
+                .map(|(_, balance)| balance)
+                .filter(|&balance| balance > 0.0)
+                .fold(max_y, f32::min);
+            self.draw_with::<_, LogCoord<f32>>(
+                area,
+                (min_y.min(max_y / 2.0)..max_y).log_scale().into(),
+            )
+        } else {
+            self.draw_with::<_, RangedCoordf32>(area, (0.0..max_y).into())
+        }
+    }
+}
+
+/// Median bankroll of each player over many sessions, surrounded by bands from the 5th to 95th
+/// and 25th to 75th percentiles, with one panel per player
+pub struct FanChart<'a> {
+    pub series: Vec<(&'a str, &'a BankrollBands)>,
+}
+
+impl Figure for FanChart<'_> {
+    fn draw<DB: DrawingBackend>(&self, area: &DrawingArea<DB, Shift>) -> Result<(), Box<dyn Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        area.fill(&WHITE)?;
+        let columns = (self.series.len() as f64).sqrt().ceil().max(1.0) as usize;
+        let rows = self.series.len().div_ceil(columns).max(1);
+        let panels = area.split_evenly((rows, columns));
+
+        for (i, (&(name, bands), panel)) in self.series.iter().zip(&panels).enumerate() {
+            let colour = Palette99::pick(i);
+            let band = |low: usize, high: usize| -> Vec<(usize, f32)> {
+                let upper = bands.rounds.iter().zip(&bands.percentiles);
+                let lower = upper.clone().rev();
+                upper
+                    .map(|(&round, p)| (round, p[high]))
+                    .chain(lower.map(|(&round, p)| (round, p[low])))
+                    .collect()
+            };
+            let max_y = bands.percentiles.iter().map(|p| p[4]).fold(1.0, f32::max);
+            let horizon = bands.horizon().max(1);
+
+            let mut chart = ChartBuilder::on(panel)
+                .margin(10)
+                .caption(name, ("sans-serif", 20))
+                .x_label_area_size(30)
+                .y_label_area_size(50)
+                .build_cartesian_2d(0..horizon, 0.0..max_y * 1.05)?;
+
+            chart
+                .configure_mesh()
+                .x_label_formatter(&|&x| format!("{x}"))
+                .y_label_formatter(&|&y| format!("${y:.0}"))
+                .x_labels(6)
+                .y_labels(8)
+                .x_desc("Rounds")
+                .axis_desc_style(("sans-serif", 12))
+                .draw()?;
+
+            chart.draw_series([
+                Polygon::new(band(0, 4), colour.mix(0.15)),
+                Polygon::new(band(1, 3), colour.mix(0.3)),
+            ])?;
+            chart.draw_series(LineSeries::new(
+                bands
+                    .rounds
+                    .iter()
+                    .zip(&bands.percentiles)
+                    .map(|(&r, p)| (r, p[2])),
+                colour.stroke_width(2),
+            ))?;
+
+            let ruin = format!(
+                "{:.1}% ruined by round {horizon}",
+                bands.ruin.probability() * 100.0
+            );
+            let style = TextStyle::from(("sans-serif", 14).into_font())
+                .color(&RED)
+                .pos(Pos::new(HPos::Right, VPos::Top));
+            chart.draw_series([Text::new(ruin, (horizon, max_y * 1.05), style)])?;
+        }
+        Ok(())
+    }
+}
+
+/// How many sessions ended with each range of bankrolls
+pub struct BankrollHistogram<'a> {
+    pub title: &'a str,
+    /// Final bankroll of each session
+    pub bankrolls: &'a [f32],
+    pub bins: usize,
+}
+
+impl Figure for BankrollHistogram<'_> {
+    fn draw<DB: DrawingBackend>(&self, area: &DrawingArea<DB, Shift>) -> Result<(), Box<dyn Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        let min = self.bankrolls.iter().copied().fold(f32::MAX, f32::min);
+        let max = self.bankrolls.iter().copied().fold(f32::MIN, f32::max);
+        let (min, max) = if min < max {
+            (min, max)
+        } else {
+            (0.0, min.max(1.0))
+        };
+        let bins = self.bins.max(1);
+        let width = (max - min) / bins as f32;
+
+        let mut counts = vec![0usize; bins];
+        for &bankroll in self.bankrolls {
+            let bin = ((bankroll - min) / width) as usize;
+            counts[bin.min(bins - 1)] += 1;
+        }
+        let highest = counts.iter().copied().max().unwrap_or(0).max(1);
+
+        area.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(area)
+            .margin(10)
+            .caption(self.title, ("sans-serif", 30))
+            .x_label_area_size(40)
+            .y_label_area_size(50)
+            .build_cartesian_2d(min..max, 0..highest)?;
+
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_label_formatter(&|&x| format!("${x:.0}"))
+            .x_desc("Final bankroll")
+            .y_desc("Sessions")
+            .axis_desc_style(("sans-serif", 15))
+            .draw()?;
+
+        chart.draw_series(counts.iter().enumerate().map(|(i, &count)| {
+            let left = min + width * i as f32;
+            Rectangle::new(
+                [(left, 0), (left + width, count)],
+                Palette99::pick(0).filled(),
+            )
+        }))?;
+        Ok(())
+    }
+}
+
+/// Expected value per initial bet at each true count, with bars coloured by whether the player
+/// had the edge
+pub struct CountChart<'a> {
+    pub title: &'a str,
+    pub by_count: &'a CountStatistics,
+}
+
+impl Figure for CountChart<'_> {
+    fn draw<DB: DrawingBackend>(&self, area: &DrawingArea<DB, Shift>) -> Result<(), Box<dyn Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        let bars: Vec<_> = self
+            .by_count
+            .buckets()
+            .map(|(count, statistics)| {
+                let ev = statistics.summary(0.0).ev_per_initial_bet * 100.0;
+                (count, ev)
+            })
+            .collect();
+        let low = bars.iter().map(|&(count, _)| count).min().unwrap_or(0);
+        let high = bars.iter().map(|&(count, _)| count).max().unwrap_or(0);
+        let extent = bars.iter().map(|&(_, ev)| ev.abs()).fold(1.0, f64::max);
+
+        area.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(area)
+            .margin(10)
+            .caption(self.title, ("sans-serif", 30))
+            .x_label_area_size(40)
+            .y_label_area_size(50)
+            .build_cartesian_2d((low..high + 1).into_segmented(), -extent..extent)?;
+
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_label_formatter(&|count| match count {
+                SegmentValue::Exact(count) | SegmentValue::CenterOf(count) => format!("{count:+}"),
+                SegmentValue::Last => String::new(),
+            })
+            .y_label_formatter(&|&y| format!("{y:+.0}%"))
+            .x_desc("True count")
+            .y_desc("EV per initial bet")
+            .axis_desc_style(("sans-serif", 15))
+            .draw()?;
+
+        chart.draw_series(bars.iter().map(|&(count, ev)| {
+            let colour = if ev >= 0.0 { GREEN } else { RED };
+            let mut bar = Rectangle::new(
+                [
+                    (SegmentValue::Exact(count), 0.0),
+                    (SegmentValue::Exact(count + 1), ev),
+                ],
+                colour.filled(),
+            );
+            bar.set_margin(0, 0, 5, 5);
+            bar
+        }))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HandOutcome, RoundResult};
+
+    use super::*;
+
+    #[test]
+    fn save() {
+        let dir = std::env::temp_dir().join(format!("midas-charts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let check = |figure: &dyn Fn(&Path), name: &str| {
+            for extension in ["svg", "png"] {
+                let path = dir.join(format!("{name}.{extension}"));
+                figure(&path);
+                assert!(std::fs::metadata(&path).unwrap().len() > 0);
+            }
+        };
+
+        let mut balances = TimeSeries::new(100);
+        [100.0, 110.0, 90.0, 120.0]
+            .into_iter()
+            .enumerate()
+            .for_each(|(round, balance)| balances.push(round, balance));
+        let balance = BalanceChart {
+            series: vec![("Player 0", &balances)],
+            first_round: 0,
+            last_round: 10,
+            hidden: vec![],
+            log_scale: true,
+        };
+        check(&|path| balance.save(path, (400, 300)).unwrap(), "balance");
+
+        let histogram = BankrollHistogram {
+            title: "Final bankrolls",
+            bankrolls: &[0.0, 50.0, 150.0, 200.0, 210.0],
+            bins: 4,
+        };
+        check(
+            &|path| histogram.save(path, (400, 300)).unwrap(),
+            "bankrolls",
+        );
+
+        let mut by_count = CountStatistics::default();
+        for true_count in [-2.0, 0.5, 3.0] {
+            let net = if true_count < 0.0 { -10.0 } else { 10.0 };
+            by_count.record(&[HandOutcome {
+                player: 0,
+                result: RoundResult::Win,
+                bet: 10.0,
+                wagered: 10.0,
+                net,
+                true_count,
+            }]);
+        }
+        let counts = CountChart {
+            title: "EV by true count",
+            by_count: &by_count,
+        };
+        check(&|path| counts.save(path, (400, 300)).unwrap(), "counts");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn time_series() {
+        let mut series = TimeSeries::new(100);
+        for x in 0..100_000 {
+            let value = match x {
+                12_345 => 500.0,
+                67_890 => -500.0,
+                _ => (x as f32 / 1000.0).sin(),
+            };
+            series.push(x, value);
+        }
+        assert!(series.buckets.len() <= 100);
+        assert_eq!(series.max(), Some(500.0));
+        let points: Vec<_> = series.points().collect();
+        assert!(points.len() <= 300);
+        assert!(points.is_sorted_by_key(|&(x, _)| x));
+        assert!(points.contains(&(12_345, 500.0)));
+        assert!(points.contains(&(67_890, -500.0)));
+        assert_eq!(points.last(), Some(&(99_999, (99.999f32).sin())));
+
+        series.drop_before(50_000);
+        assert!(series.points().all(|(x, _)| x >= 49_000));
+        assert!(!series.points().any(|point| point == (12_345, 500.0)));
+    }
+}
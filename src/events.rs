@@ -0,0 +1,264 @@
+//! Structured record of everything that happens at the table, for auditing strategy decisions
+//! and building tools on top of a game.
+//!
+//! Events are saved as JSON Lines as they happen, one event per line, each tagged with the
+//! index of the session it came from. The first line is a [`Header`] with the table the events
+//! were recorded at.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::RoundResult;
+use crate::cards::Card;
+use crate::experiment::Seat;
+use crate::player::{Action, AvailableActions};
+use crate::rules::Rules;
+
+/// Something that happened during a game, in the order it happened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// A new round started, numbered from 1
+    Round { round: usize },
+    /// The discards were shuffled back into the shoe
+    Shuffle,
+    /// A card was discarded without being shown after a shuffle
+    Burn { card: Card },
+    /// A player bet on a new hand
+    Bet { player: u8, amount: f32 },
+    /// A card was dealt from the shoe, face down only for the dealer's hole card
+    Deal {
+        card: Card,
+        to: Recipient,
+        face_up: bool,
+    },
+    /// A player chose an action for one of their hands
+    Decision {
+        player: u8,
+        hand: usize,
+        action: Action,
+        available: AvailableActions,
+    },
+    /// The dealer turned over their hole card
+    Reveal { card: Card },
+    /// The dealer drew a card after the players had finished
+    DealerDraw { card: Card },
+    /// A hand was settled, with `net` won or negative if lost
+    Settle {
+        player: u8,
+        hand: usize,
+        result: RoundResult,
+        net: f32,
+    },
+    /// A player's balance once the round was settled
+    Balance { player: u8, balance: f32 },
+}
+
+/// Who a card was dealt to, with hands numbered in the order they were created in the round
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Recipient {
+    Dealer,
+    Player { player: u8, hand: usize },
+}
+
+/// Called with every event as it happens
+pub(crate) type Observer = Box<dyn FnMut(&Event)>;
+
+/// Events kept by a game while recording or passed to its observer, so nothing is built when
+/// no one is listening
+#[derive(Default)]
+pub(crate) struct EventLog {
+    pub(crate) enabled: bool,
+    pub(crate) events: Vec<Event>,
+    pub(crate) observer: Option<Observer>,
+}
+
+impl EventLog {
+    pub(crate) fn push(&mut self, event: impl FnOnce() -> Event) {
+        if !self.enabled && self.observer.is_none() {
+            return;
+        }
+        let event = event();
+        if let Some(observer) = &mut self.observer {
+            observer(&event);
+        }
+        if self.enabled {
+            self.events.push(event);
+        }
+    }
+}
+
+/// The table a recording was made at, so it can be replayed the same way
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    pub rules: Rules,
+    /// Every seat in id order
+    pub seats: Vec<Seat>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HeaderLine {
+    header: Header,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Line {
+    session: usize,
+    #[serde(flatten)]
+    event: Event,
+}
+
+/// Writes events as JSON Lines as they are recorded. Events from sessions played at the same
+/// time may be interleaved, but each session's events stay in order.
+pub struct EventWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> EventWriter<W> {
+    /// Writer that starts with `header`
+    pub fn new(mut writer: W, header: &Header) -> io::Result<Self> {
+        let line = HeaderLine {
+            header: header.clone(),
+        };
+        serde_json::to_writer(&mut writer, &line)?;
+        writeln!(writer)?;
+        Ok(Self { writer })
+    }
+
+    /// Write `events`, which happened in the session at `index`
+    pub fn write(&mut self, index: usize, events: &[Event]) -> io::Result<()> {
+        for event in events {
+            let line = Line {
+                session: index,
+                event: event.clone(),
+            };
+            serde_json::to_writer(&mut self.writer, &line)?;
+            writeln!(self.writer)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl EventWriter<BufWriter<File>> {
+    /// Writer for events saved to a file at `path`
+    pub fn create(path: impl AsRef<Path>, header: &Header) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), header)
+    }
+}
+
+/// Read the header and events written by an [`EventWriter`], with events grouped by session
+pub fn read_events(reader: impl BufRead) -> io::Result<(Header, Vec<Vec<Event>>)> {
+    let mut lines = reader
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()));
+    let first = lines.next().transpose()?.unwrap_or_default();
+    let HeaderLine { header } = serde_json::from_str(&first).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("the events don't start with a header of the table they were recorded at: {e}"),
+        )
+    })?;
+
+    let mut sessions: Vec<Vec<Event>> = vec![];
+    for line in lines {
+        let Line { session, event } = serde_json::from_str(&line?)?;
+        if sessions.len() <= session {
+            sessions.resize_with(session + 1, Vec::new);
+        }
+        sessions[session].push(event);
+    }
+    Ok((header, sessions))
+}
+
+/// Read the header and events from a file at `path`, with events grouped by session
+pub fn load_events(path: impl AsRef<Path>) -> io::Result<(Header, Vec<Vec<Event>>)> {
+    read_events(BufReader::new(File::open(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::ai::action::SimpleActionStrategy;
+    use crate::ai::betting::FlatBettingStrategy;
+    use crate::ai::registry::StrategySpec;
+    use crate::game::Game;
+    use crate::player::Player;
+    use crate::session::{Session, StopCondition};
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let players = (0..2)
+            .map(|id| {
+                Player::new(
+                    id,
+                    1000.0,
+                    Box::new(SimpleActionStrategy),
+                    Box::new(FlatBettingStrategy),
+                )
+            })
+            .collect();
+        let rules = Rules {
+            penetration: 0.5,
+            burn_cards: 1,
+            ..Rules::default()
+        };
+        let mut game = Game::with_seed(players, rules, 5);
+        let observed = Rc::new(RefCell::new(vec![]));
+        let log = observed.clone();
+        game.observe(move |event| log.borrow_mut().push(event.clone()));
+        let mut session = Session::new(vec![StopCondition::Shoes(2)]);
+        session.record_events = true;
+        let mut events = vec![];
+        let seat = Seat {
+            action: StrategySpec::new("simple"),
+            betting: StrategySpec::new("flat"),
+            bankroll: 1000.0,
+            name: None,
+        };
+        let header = Header {
+            rules,
+            seats: vec![seat; 2],
+        };
+        let mut writer = EventWriter::new(vec![], &header).unwrap();
+        let result = session
+            .play_with(&mut game, |game| {
+                let round = game.take_events();
+                writer.write(0, &round)?;
+                events.extend(round);
+                Ok::<_, io::Error>(())
+            })
+            .unwrap();
+        assert_eq!(*observed.borrow(), events);
+
+        let count = |matches: fn(&Event) -> bool| events.iter().filter(|e| matches(e)).count();
+        assert_eq!(count(|e| matches!(e, Event::Round { .. })), result.rounds);
+        assert_eq!(count(|e| matches!(e, Event::Shuffle)), 2);
+        assert_eq!(count(|e| matches!(e, Event::Burn { .. })), 3);
+        let hands: i32 = result
+            .players
+            .iter()
+            .map(|p| p.results.values().sum::<i32>())
+            .sum();
+        assert_eq!(count(|e| matches!(e, Event::Settle { .. })), hands as usize);
+        assert_eq!(count(|e| matches!(e, Event::Reveal { .. })), result.rounds);
+
+        assert_eq!(
+            read_events(writer.writer.as_slice()).unwrap(),
+            (header, vec![events])
+        );
+        let headless = "{\"session\":0,\"event\":\"shuffle\"}\n";
+        assert!(read_events(headless.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ai::registry::{Registry, StrategySpec};
+use crate::game::Game;
+use crate::player::Player;
+use crate::rules::Rules;
+use crate::session::{Session, StopCondition};
+use crate::simulation::Simulation;
+
+/// Table rules, the players seated at it and how to run them, usually read from a TOML file:
+///
+/// ```toml
+/// [rules]
+/// decks = 6
+/// penetration = 0.75
+///
+/// [[seats]]
+/// action = "optimal"
+/// betting = { type = "hilo", ramp = [1, 1, 2, 4, 8] }
+/// bankroll = 1000
+///
+/// [run]
+/// sessions = 10000
+/// seed = 1
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Experiment {
+    pub rules: Rules,
+    pub seats: Vec<Seat>,
+    pub run: RunSettings,
+}
+
+/// A player's strategies and starting bankroll
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Seat {
+    pub action: StrategySpec,
+    pub betting: StrategySpec,
+    pub bankroll: f32,
+    /// Display name, named after the strategies if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// How many sessions to run, and when each of them stops
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RunSettings {
+    pub sessions: usize,
+    /// Random if not given
+    pub seed: Option<u64>,
+    /// Every available core if not given
+    pub threads: Option<usize>,
+    /// Path to save each session's results to
+    pub output: Option<PathBuf>,
+    /// Path to save every hand to, if given
+    pub hands_output: Option<PathBuf>,
+    /// Path to save every event at the table to as JSON Lines, if given
+    pub events_output: Option<PathBuf>,
+    /// Defaults to 100,000 so that winning strategies still finish
+    pub max_rounds: Option<usize>,
+    pub max_shoes: Option<usize>,
+    pub max_seconds: Option<f64>,
+    /// Balance at which a player stops playing
+    pub goal: Option<f32>,
+    /// Playing speed used for hourly results
+    pub hands_per_hour: f64,
+    /// Risk of ruin to find the required bankroll for
+    pub target_ror: f64,
+}
+
+impl Default for RunSettings {
+    fn default() -> Self {
+        Self {
+            sessions: 100_000,
+            seed: None,
+            threads: None,
+            output: None,
+            hands_output: None,
+            events_output: None,
+            max_rounds: Some(Session::ROUND_LIMIT),
+            max_shoes: None,
+            max_seconds: None,
+            goal: None,
+            hands_per_hour: 100.0,
+            target_ror: 0.05,
+        }
+    }
+}
+
+impl Experiment {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Ok(fs::read_to_string(path)?.parse()?)
+    }
+
+    /// Create a player for each seat, with ids in seat order
+    pub fn players(&self, registry: &Registry) -> Result<Vec<Player>, Box<dyn Error>> {
+        self.seats
+            .iter()
+            .enumerate()
+            .map(|(id, seat)| {
+                let mut player = Player::new(
+                    id as u8,
+                    seat.bankroll,
+                    registry.action(&seat.action, &self.rules)?,
+                    registry.betting(&seat.betting, &self.rules)?,
+                );
+                player.name = seat.name();
+                Ok(player)
+            })
+            .collect()
+    }
+
+    /// New game with the experiment's rules, seeded if a seed was given
+    pub fn game(&self, players: Vec<Player>) -> Game {
+        match self.run.seed {
+            Some(seed) => Game::with_seed(players, self.rules, seed),
+            None => Game::with_rules(players, self.rules),
+        }
+    }
+
+    pub fn simulation(&self) -> Simulation {
+        let mut simulation = Simulation::new(self.rules, self.run.sessions);
+        if let Some(seed) = self.run.seed {
+            simulation.seed = seed;
+        }
+        if let Some(threads) = self.run.threads {
+            simulation.threads = threads;
+        }
+        simulation
+    }
+}
+
+impl FromStr for Experiment {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+impl Seat {
+    /// The seat's display name, or a short name from its strategies, e.g. `optimal/hilo`
+    pub fn name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("{}/{}", self.action.name, self.betting.name),
+        }
+    }
+}
+
+impl FromStr for Seat {
+    type Err = String;
+
+    /// Parse a seat written as `action:betting:bankroll` or `action:betting:bankroll:name`,
+    /// using strategies without parameters
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.splitn(4, ':').collect();
+        let (action, betting, bankroll, name) = match parts[..] {
+            [action, betting, bankroll] => (action, betting, bankroll, None),
+            [action, betting, bankroll, name] => (action, betting, bankroll, Some(name)),
+            _ => {
+                return Err(format!(
+                    "expected `action:betting:bankroll[:name]`, got `{s}`"
+                ));
+            }
+        };
+        let bankroll = bankroll
+            .parse()
+            .map_err(|_| format!("invalid bankroll `{bankroll}`"))?;
+
+        Ok(Self {
+            action: StrategySpec::new(action),
+            betting: StrategySpec::new(betting),
+            bankroll,
+            name: name.map(str::to_string),
+        })
+    }
+}
+
+impl RunSettings {
+    /// Session that stops on any of the configured conditions, or once every player is broke
+    pub fn session(&self) -> Session {
+        let mut stop = vec![StopCondition::Bankrupt];
+        if let Some(rounds) = self.max_rounds {
+            stop.push(StopCondition::Rounds(rounds));
+        }
+        if let Some(shoes) = self.max_shoes {
+            stop.push(StopCondition::Shoes(shoes));
+        }
+        if let Some(seconds) = self.max_seconds {
+            stop.push(StopCondition::Time(Duration::from_secs_f64(seconds)));
+        }
+        if let Some(goal) = self.goal {
+            stop.push(StopCondition::Goal(goal));
+        }
+        let mut session = Session::new(stop);
+        session.record_hands = self.hands_output.is_some();
+        session.record_events = self.events_output.is_some();
+        session
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let experiment: Experiment = r#"
+            [rules]
+            decks = 6
+            dealer_peeks = true
+            surrender = true
+
+            [[seats]]
+            action = "optimal"
+            betting = { type = "hilo", ramp = [1, 1, 2, 4, 8] }
+            bankroll = 1000
+
+            [[seats]]
+            action = "simple"
+            betting = "flat"
+            bankroll = 500
+            name = "Basic"
+
+            [run]
+            sessions = 10
+            seed = 1
+            max_shoes = 2
+            "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(experiment.rules.decks, 6);
+        assert!(experiment.rules.surrender);
+        assert_eq!(
+            experiment.seats[1],
+            "simple:flat:500:Basic".parse().unwrap()
+        );
+        assert_eq!(experiment.seats[0].name(), "optimal/hilo");
+        assert_eq!(experiment.seats[1].name(), "Basic");
+        assert_eq!(experiment.run.max_rounds, Some(100_000));
+
+        let players = experiment.players(&Registry::default()).unwrap();
+        assert_eq!(players.len(), 2);
+        assert_eq!(players[1].name, "Basic");
+        let result = experiment.run.session().play(&mut experiment.game(players));
+        assert_eq!(result.stopped, StopCondition::Shoes(2));
+
+        assert!(
+            "[rules]\nseats = 3\nunknown = 1"
+                .parse::<Experiment>()
+                .is_err()
+        );
+    }
+}
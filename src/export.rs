@@ -0,0 +1,162 @@
+//! CSV export of session results.
+//!
+//! Sessions are written one row per player per session once the simulation is done, counting
+//! a split as a single hand won, pushed or lost by its net result. Hands are written one row
+//! per hand of a split as each round is played, so they never have to be held in memory.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::HandRecord;
+use crate::session::SessionResult;
+
+const SESSION_HEADER: &str = concat!(
+    "session,player,starting_bankroll,ending_bankroll,net,rounds,hands,",
+    "wins,pushes,losses,wagered,ev_per_hand,stopped"
+);
+
+const HAND_HEADER: &str =
+    "session,round,player,cards,dealer,actions,bet,wagered,result,net,true_count";
+
+/// Write a row for every player in every session
+pub fn write_sessions(mut writer: impl Write, sessions: &[SessionResult]) -> io::Result<()> {
+    writeln!(writer, "{SESSION_HEADER}")?;
+    for (index, session) in sessions.iter().enumerate() {
+        for player in &session.players {
+            writeln!(
+                writer,
+                "{index},{},{},{},{},{},{},{},{},{},{},{},{:?}",
+                player.id,
+                player.starting_balance,
+                player.balance,
+                player.net(),
+                session.rounds,
+                player.hands,
+                player.wins,
+                player.pushes,
+                player.losses,
+                player.wagered,
+                player.statistics.summary(0.0).ev_per_hand,
+                session.stopped
+            )?;
+        }
+    }
+    writer.flush()
+}
+
+/// Writes a row for every hand as it is played, with cards and decisions separated by spaces.
+/// Rows from sessions played at the same time may be interleaved, and each one starts with its
+/// session's index.
+pub struct HandWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> HandWriter<W> {
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(writer, "{HAND_HEADER}")?;
+        Ok(Self { writer })
+    }
+
+    /// Write a row for each of `hands`, which were played in the session at `index`
+    pub fn write(&mut self, index: usize, hands: &[HandRecord]) -> io::Result<()> {
+        for hand in hands {
+            let outcome = &hand.outcome;
+            writeln!(
+                self.writer,
+                "{index},{},{},{},{},{},{},{},{:?},{},{}",
+                hand.round,
+                outcome.player,
+                join(&hand.cards),
+                join(&hand.dealer),
+                hand.actions
+                    .iter()
+                    .map(|action| format!("{action:?}"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                outcome.bet,
+                outcome.wagered,
+                outcome.result,
+                outcome.net,
+                outcome.true_count
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl HandWriter<BufWriter<File>> {
+    /// Writer for hands saved to a file at `path`
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Write a row for every player in every session to a file at `path`
+pub fn save_sessions(path: impl AsRef<Path>, sessions: &[SessionResult]) -> io::Result<()> {
+    write_sessions(BufWriter::new(File::create(path)?), sessions)
+}
+
+fn join(cards: &[impl ToString]) -> String {
+    cards
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ai::action::SimpleActionStrategy;
+    use crate::ai::betting::FlatBettingStrategy;
+    use crate::game::Game;
+    use crate::player::Player;
+    use crate::rules::Rules;
+    use crate::session::{Session, StopCondition};
+
+    use super::*;
+
+    #[test]
+    fn csv() {
+        let player = Player::new(
+            0,
+            1000.0,
+            Box::new(SimpleActionStrategy),
+            Box::new(FlatBettingStrategy),
+        );
+        let mut game = Game::with_seed(vec![player], Rules::default(), 3);
+        let mut session = Session::new(vec![StopCondition::Rounds(10)]);
+        session.record_hands = true;
+        let mut hands = HandWriter::new(vec![]).unwrap();
+        let mut played = 0;
+        let sessions = [session
+            .play_with(&mut game, |game| {
+                played += game.hand_records().len();
+                hands.write(0, game.hand_records())
+            })
+            .unwrap()];
+
+        let mut output = vec![];
+        write_sessions(&mut output, &sessions).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let row: Vec<_> = lines[1].split(',').collect();
+        assert_eq!(row.len(), SESSION_HEADER.split(',').count());
+        assert_eq!(row[5], "10");
+
+        let output = String::from_utf8(hands.writer).unwrap();
+        assert!(played >= 10);
+        assert_eq!(output.lines().count(), played + 1);
+        assert!(
+            output
+                .lines()
+                .skip(1)
+                .all(|line| line.split(',').count() == 11)
+        );
+    }
+}
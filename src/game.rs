@@ -1,45 +1,195 @@
-use crate::RoundResult;
+use crate::ai::count::{Counter, CountingSystem};
 use crate::cards::card::Face;
 use crate::cards::{Card, Hand, Shoe};
-use crate::player::{Action, Player};
+use crate::events::{Event, EventLog, Recipient};
+use crate::player::{Action, AvailableActions, Player, Seating};
+use crate::rules::Rules;
+use crate::{HandOutcome, HandRecord, RoundResult};
 
 #[derive(Debug)]
 pub struct ActiveHand {
     pub player: u8,
     pub hand: Hand,
+    /// Initial bet on the hand
+    pub bet: f32,
+    /// True count when the bet was made
+    pub true_count: f32,
     pub pot: f32,
     pub blackjack: bool,
+    pub split: bool,
+    pub surrendered: bool,
     pub completed: bool,
+    /// Every decision made on the hand, only kept when recording hands
+    pub actions: Vec<Action>,
 }
 
 pub struct Game {
+    rules: Rules,
     shoe: Shoe,
     pub players: Vec<Player>,
     dealer_hand: Hand,
     hands: Vec<ActiveHand>,
+    rounds: usize,
+    record_hands: bool,
+    hand_records: Vec<HandRecord>,
+    log: EventLog,
+    /// Cards burned since the last shuffle, kept out of the discards until the next one
+    burned: Vec<Card>,
+    /// Whether the shoe has been shuffled since cards were last burned
+    burn_pending: bool,
 }
 
 impl Game {
     pub fn new(players: Vec<Player>) -> Self {
+        Self::with_rules(players, Rules::default())
+    }
+
+    pub fn with_rules(players: Vec<Player>, rules: Rules) -> Self {
+        let shoe = Shoe::new(rules.decks);
+        Self::with_shoe(players, rules, shoe)
+    }
+
+    /// Game where the shoe is shuffled the same way every time for the same seed
+    pub fn with_seed(players: Vec<Player>, rules: Rules, seed: u64) -> Self {
+        let shoe = Shoe::seeded(rules.decks, seed);
+        Self::with_shoe(players, rules, shoe)
+    }
+
+    /// Players beyond the number of seats start out watching the table
+    pub(crate) fn with_shoe(mut players: Vec<Player>, rules: Rules, shoe: Shoe) -> Self {
         let dealer_hand = Hand::new();
-        let shoe = Shoe::new();
+        let mut seats = rules.seats;
+        for player in players.iter_mut().filter(|p| p.seated) {
+            match seats {
+                0 => player.seated = false,
+                _ => seats -= 1,
+            }
+        }
 
         Self {
+            rules,
             dealer_hand,
             players,
             hands: vec![],
             shoe,
+            rounds: 0,
+            record_hands: false,
+            hand_records: vec![],
+            log: EventLog::default(),
+            burned: vec![],
+            burn_pending: true,
         }
     }
 
-    pub fn round(&mut self) -> Vec<RoundResult> {
+    /// Add a player to the game, who will take a seat once one is available if they are seated
+    pub fn add_player(&mut self, mut player: Player) {
+        player.seated &= self.can_join();
+        self.players.push(player);
+    }
+
+    /// Remove a player from the game entirely, between rounds
+    pub fn remove_player(&mut self, id: u8) -> Option<Player> {
+        let index = self.players.iter().position(|p| p.id == id)?;
+        Some(self.players.remove(index))
+    }
+
+    /// Number of seats not taken by a player
+    pub fn open_seats(&self) -> usize {
+        let taken = self.players.iter().filter(|p| p.seated).count();
+        self.rules.seats.saturating_sub(taken)
+    }
+
+    /// Whether a player may take a seat before the next round
+    pub fn can_join(&self) -> bool {
+        self.open_seats() > 0 && (self.rules.mid_shoe_entry || self.shoe.discards.is_empty())
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Number of rounds played so far
+    pub fn rounds_played(&self) -> usize {
+        self.rounds
+    }
+
+    /// Keep the cards and decisions of every hand in the latest round, which are otherwise
+    /// discarded once the round is settled
+    pub fn record_hands(&mut self, record: bool) {
+        self.record_hands = record;
+        self.hand_records.clear();
+    }
+
+    /// Every hand of the latest round, if hands are being recorded
+    pub fn hand_records(&self) -> &[HandRecord] {
+        &self.hand_records
+    }
+
+    /// Record an [`Event`] for everything that happens at the table from now on, to be
+    /// collected with [`Game::take_events`]
+    pub fn record_events(&mut self, record: bool) {
+        self.log.enabled = record;
+    }
+
+    /// Call `observer` with every event as it happens, whether or not events are recorded, to
+    /// show the table to someone playing at it
+    pub fn observe(&mut self, observer: impl FnMut(&Event) + 'static) {
+        self.log.observer = Some(Box::new(observer));
+    }
+
+    /// Every event recorded since the last call
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.log.events)
+    }
+
+    /// Number of shoes that have been dealt out and reshuffled so far
+    pub fn shoes_completed(&self) -> usize {
+        self.shoe.reshuffles()
+    }
+
+    /// True count of the cards shown since the last shuffle, using `system`
+    pub fn true_count(&self, system: CountingSystem) -> f32 {
+        let mut counter = Counter::new(system);
+        self.shoe
+            .discards
+            .iter()
+            .chain(self.hands.iter().flat_map(|hand| &hand.hand.cards))
+            .for_each(|card| counter.card_dealt(card));
+        counter.true_count(self.rules.decks)
+    }
+
+    /// Play a single round, returning the outcome of every hand
+    pub fn round(&mut self) -> Vec<HandOutcome> {
+        let round = self.rounds + 1;
+        self.log.push(|| Event::Round { round });
+        if self.shoe.past_cut(self.rules.penetration) {
+            self.shoe.discards.append(&mut self.burned);
+            self.shoe.reshuffle();
+            self.log.push(|| Event::Shuffle);
+            self.players.iter_mut().for_each(|p| p.shuffled());
+            self.burn_pending = true;
+        }
+        if self.burn_pending {
+            self.burn();
+        }
         self.initial_deal();
 
         let shown = self.dealer_hand.cards.first().unwrap();
 
+        // Dealer peeks for blackjack, ending the round before any decisions are made
+        if self.rules.dealer_peeks && shown.face.value() >= 10 && self.dealer_hand.value() == 21 {
+            self.hands.iter_mut().for_each(|h| h.completed = true);
+        }
+
         let mut splits = vec![];
         while self.hands.iter().any(|h| !h.completed) {
-            for active_hand in self.hands.iter_mut().filter(|h| !h.completed) {
+            let existing = self.hands.len();
+            for (index, active_hand) in self
+                .hands
+                .iter_mut()
+                .enumerate()
+                .filter(|(_, h)| !h.completed)
+            {
                 if active_hand.hand.value() >= 21 {
                     active_hand.completed = true;
                     continue;
@@ -51,25 +201,52 @@ impl Game {
                     .find(|x| x.id == active_hand.player)
                     .expect("All hands should have a valid player");
 
-                let action = player.action(&active_hand.hand, shown);
+                let initial_cards = active_hand.hand.cards.len() == 2;
+                let available = AvailableActions {
+                    double: initial_cards && (!active_hand.split || self.rules.double_after_split),
+                    split: active_hand.hand.is_pair(),
+                    surrender: initial_cards && !active_hand.split && self.rules.surrender,
+                };
+
+                let action = player.action(&active_hand.hand, shown, &available);
+                if self.record_hands {
+                    active_hand.actions.push(action);
+                }
+                let id = active_hand.player;
+                self.log.push(|| Event::Decision {
+                    player: id,
+                    hand: index,
+                    action,
+                    available,
+                });
+                let to = Recipient::Player {
+                    player: id,
+                    hand: index,
+                };
                 match action {
-                    Action::Hit => active_hand
-                        .hand
-                        .add_card(deal(&mut self.shoe, &mut self.players)),
+                    Action::Hit => active_hand.hand.add_card(deal(
+                        &mut self.shoe,
+                        &mut self.players,
+                        &mut self.log,
+                        to,
+                    )),
                     Action::Stand => {
                         active_hand.completed = true;
                     }
                     Action::Double => {
-                        assert_eq!(active_hand.hand.cards.len(), 2);
+                        assert!(available.double);
                         player.balance -= active_hand.pot;
                         active_hand.pot *= 2.;
-                        active_hand
-                            .hand
-                            .add_card(deal(&mut self.shoe, &mut self.players));
+                        active_hand.hand.add_card(deal(
+                            &mut self.shoe,
+                            &mut self.players,
+                            &mut self.log,
+                            to,
+                        ));
                         active_hand.completed = true;
                     }
                     Action::Split => {
-                        assert!(active_hand.hand.is_pair());
+                        assert!(available.split);
                         player.balance -= active_hand.pot;
                         let second_card = active_hand
                             .hand
@@ -78,19 +255,42 @@ impl Game {
                             .expect("Hand guaranteed to be 2 cards");
                         let mut new_hand = Hand::new();
                         new_hand.cards.push(second_card);
-                        active_hand
-                            .hand
-                            .add_card(deal(&mut self.shoe, &mut self.players));
-                        new_hand.add_card(deal(&mut self.shoe, &mut self.players));
+                        active_hand.hand.add_card(deal(
+                            &mut self.shoe,
+                            &mut self.players,
+                            &mut self.log,
+                            to,
+                        ));
+                        let split_to = Recipient::Player {
+                            player: id,
+                            hand: existing + splits.len(),
+                        };
+                        new_hand.add_card(deal(
+                            &mut self.shoe,
+                            &mut self.players,
+                            &mut self.log,
+                            split_to,
+                        ));
                         splits.push(ActiveHand {
                             player: active_hand.player,
                             hand: new_hand,
+                            bet: active_hand.bet,
+                            true_count: active_hand.true_count,
                             pot: active_hand.pot,
                             blackjack: false, // Splits can never be a real blackjack
+                            split: true,
+                            surrendered: false,
                             completed: second_card.face == Face::Ace,
+                            actions: active_hand.actions.clone(),
                         });
+                        active_hand.split = true;
                         active_hand.completed = second_card.face == Face::Ace; // Can only split aces once
                     }
+                    Action::Surrender => {
+                        assert!(available.surrender);
+                        active_hand.surrendered = true;
+                        active_hand.completed = true;
+                    }
                 }
             }
             self.hands.append(&mut splits);
@@ -102,36 +302,106 @@ impl Game {
     fn initial_deal(&mut self) {
         for i in 0..self.players.len() {
             // Don't deal in players that can't make minimum bet
-            if self.players[i].balance < 10.0 {
+            if self.players[i].balance < self.rules.min_bet {
                 continue;
             }
 
-            let bet = self.players[i].bet();
-            let mut hand = Hand::new();
-            hand.add_card(deal(&mut self.shoe, &mut self.players));
-            hand.add_card(deal(&mut self.shoe, &mut self.players));
+            // Players watching the table may only join when there's a seat
+            let seated = self.players[i].seated;
+            match self.players[i].seating() {
+                Seating::Play if seated => {}
+                Seating::Play if self.can_join() => self.players[i].seated = true,
+                Seating::Play | Seating::SitOut => continue,
+                Seating::Leave => {
+                    self.players[i].seated = false;
+                    continue;
+                }
+            }
+
+            // The count the player bets on, or the table's count in their system if they
+            // don't keep a true count, and in Hi-Lo if they don't count at all
+            let true_count = self.players[i].true_count().unwrap_or_else(|| {
+                let system = self.players[i].counting_system();
+                self.true_count(system.unwrap_or(CountingSystem::HI_LO))
+            });
+            let Some(bet) = self.players[i].bet(self.rules.min_bet, self.rules.max_bet) else {
+                continue;
+            };
             let player = self.players[i].id;
+            self.log.push(|| Event::Bet {
+                player,
+                amount: bet,
+            });
+            let to = Recipient::Player {
+                player,
+                hand: self.hands.len(),
+            };
+            let mut hand = Hand::new();
+            for _ in 0..2 {
+                hand.add_card(deal(&mut self.shoe, &mut self.players, &mut self.log, to));
+            }
             let blackjack = hand.value() == 21;
             self.hands.push(ActiveHand {
                 player,
                 hand,
+                bet,
+                true_count,
                 pot: bet,
                 blackjack,
+                split: false,
+                surrendered: false,
                 completed: false,
+                actions: vec![],
             });
         }
 
-        self.dealer_hand
-            .add_card(deal(&mut self.shoe, &mut self.players));
-        self.dealer_hand
-            .add_card(secret_deal(&mut self.shoe, &mut self.players));
+        self.dealer_hand.add_card(deal(
+            &mut self.shoe,
+            &mut self.players,
+            &mut self.log,
+            Recipient::Dealer,
+        ));
+        self.dealer_hand.add_card(secret_deal(
+            &mut self.shoe,
+            &mut self.players,
+            &mut self.log,
+        ));
     }
 
-    fn finish_round(&mut self) -> Vec<RoundResult> {
-        // Dealer hits until at least 17
-        while self.dealer_hand.value() < 17 {
-            self.dealer_hand
-                .add_card(deal(&mut self.shoe, &mut self.players));
+    /// Discard cards from the top of a freshly shuffled shoe without showing them
+    fn burn(&mut self) {
+        self.burn_pending = false;
+        for _ in 0..self.rules.burn_cards {
+            let (card, _) = self.shoe.deal();
+            self.log.push(|| Event::Burn { card });
+            self.burned.push(card);
+        }
+    }
+
+    fn finish_round(&mut self) -> Vec<HandOutcome> {
+        // Reveal the hole card
+        let hole_card = self.dealer_hand.cards[1];
+        self.log.push(|| Event::Reveal { card: hole_card });
+        self.players
+            .iter_mut()
+            .for_each(|p| p.card_dealt(&hole_card));
+
+        // Dealer hits until at least 17, and optionally on a soft 17
+        while self.dealer_hand.value() < 17
+            || (self.rules.dealer_hits_soft_17
+                && self.dealer_hand.value() == 17
+                && self.dealer_hand.is_soft())
+        {
+            let (card, shuffled) = self.shoe.deal();
+            if shuffled {
+                self.log.push(|| Event::Shuffle);
+            }
+            self.log.push(|| Event::DealerDraw { card });
+            self.players.iter_mut().for_each(|p| p.card_dealt(&card));
+            if shuffled {
+                self.players.iter_mut().for_each(|p| p.shuffled());
+            }
+            self.dealer_hand.add_card(card);
         }
 
         let dealer_value = self.dealer_hand.value();
@@ -140,11 +410,13 @@ impl Game {
 
         // Calculate round results for each hand
         let mut results = vec![];
-        for hand in &self.hands {
+        for (index, hand) in self.hands.iter().enumerate() {
             let player_value = hand.hand.value();
             let player_bust = player_value > 21;
 
-            let result = if hand.blackjack && !dealer_blackjack {
+            let result = if hand.surrendered {
+                RoundResult::Surrender
+            } else if hand.blackjack && !dealer_blackjack {
                 RoundResult::Blackjack
             } else if player_bust
                 || (dealer_blackjack && !hand.blackjack)
@@ -159,19 +431,66 @@ impl Game {
 
             // Update player's cash stack with any winnings
             let winnings = match result {
-                RoundResult::Blackjack => hand.pot * 2.5,
+                RoundResult::Blackjack => hand.pot * (1.0 + self.rules.blackjack_payout),
                 RoundResult::Win => hand.pot * 2.0,
                 RoundResult::Push => hand.pot,
+                RoundResult::Surrender => hand.pot * 0.5,
                 _ => 0.,
             };
 
-            self.players
+            let player = self
+                .players
                 .iter_mut()
                 .find(|p| p.id == hand.player)
-                .unwrap()
-                .balance += winnings;
+                .unwrap();
+            player.balance += winnings;
+            let outcome = HandOutcome {
+                player: hand.player,
+                result,
+                bet: hand.bet,
+                wagered: hand.pot,
+                net: winnings - hand.pot,
+                true_count: hand.true_count,
+            };
+            player.result(&outcome);
+            self.log.push(|| Event::Settle {
+                player: hand.player,
+                hand: index,
+                result,
+                net: outcome.net,
+            });
+
+            results.push(outcome);
+        }
+
+        let mut settled = vec![];
+        for hand in &self.hands {
+            if !settled.contains(&hand.player) {
+                settled.push(hand.player);
+            }
+        }
+        for id in settled {
+            if let Some(player) = self.players.iter().find(|p| p.id == id) {
+                let balance = player.balance;
+                self.log.push(|| Event::Balance {
+                    player: id,
+                    balance,
+                });
+            }
+        }
 
-            results.push(result);
+        self.rounds += 1;
+        self.hand_records.clear();
+        if self.record_hands {
+            for (hand, outcome) in self.hands.iter_mut().zip(&results) {
+                self.hand_records.push(HandRecord {
+                    round: self.rounds,
+                    outcome: *outcome,
+                    cards: hand.hand.cards.clone(),
+                    dealer: self.dealer_hand.cards.clone(),
+                    actions: std::mem::take(&mut hand.actions),
+                });
+            }
         }
 
         // Clean up game state
@@ -185,8 +504,16 @@ impl Game {
     }
 }
 
-fn deal(shoe: &mut Shoe, players: &mut Vec<Player>) -> Card {
+fn deal(shoe: &mut Shoe, players: &mut [Player], log: &mut EventLog, to: Recipient) -> Card {
     let (card, shuffled) = shoe.deal();
+    if shuffled {
+        log.push(|| Event::Shuffle);
+    }
+    log.push(|| Event::Deal {
+        card,
+        to,
+        face_up: true,
+    });
 
     players.iter_mut().for_each(|p| p.card_dealt(&card));
     if shuffled {
@@ -196,8 +523,16 @@ fn deal(shoe: &mut Shoe, players: &mut Vec<Player>) -> Card {
     card
 }
 
-fn secret_deal(shoe: &mut Shoe, players: &mut Vec<Player>) -> Card {
+fn secret_deal(shoe: &mut Shoe, players: &mut [Player], log: &mut EventLog) -> Card {
     let (card, shuffled) = shoe.deal();
+    if shuffled {
+        log.push(|| Event::Shuffle);
+    }
+    log.push(|| Event::Deal {
+        card,
+        to: Recipient::Dealer,
+        face_up: false,
+    });
 
     if shuffled {
         players.iter_mut().for_each(|p| p.shuffled());
@@ -205,3 +540,218 @@ fn secret_deal(shoe: &mut Shoe, players: &mut Vec<Player>) -> Card {
 
     card
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ai::action::{ActionStrategy, SimpleActionStrategy};
+    use crate::ai::betting::{BettingStrategy, RampBettingStrategy, WongingStrategy};
+    use crate::cards::card::Suit;
+
+    use super::*;
+
+    /// Bets the same amount every round
+    struct Fixed(f32);
+
+    impl BettingStrategy for Fixed {
+        fn bet(&mut self, _remaining: f32) -> f32 {
+            self.0
+        }
+
+        fn card_dealt(&mut self, _card: &Card) {}
+        fn shuffled(&mut self) {}
+    }
+
+    struct Stand;
+
+    impl ActionStrategy for Stand {
+        fn action(
+            &self,
+            _hand: &Hand,
+            _dealer_card: &Card,
+            _available: &AvailableActions,
+        ) -> Action {
+            Action::Stand
+        }
+    }
+
+    fn player(id: u8, betting: impl BettingStrategy + 'static) -> Player {
+        Player::new(
+            id,
+            1000.0,
+            Box::new(SimpleActionStrategy),
+            Box::new(betting),
+        )
+    }
+
+    fn bets(game: &mut Game) -> Vec<(u8, f32)> {
+        game.take_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::Bet { player, amount } => Some((player, amount)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn table_limits() {
+        let rules = Rules {
+            min_bet: 10.0,
+            max_bet: 50.0,
+            ..Rules::default()
+        };
+        let players = vec![player(0, Fixed(5.0)), player(1, Fixed(100.0))];
+        let mut game = Game::with_seed(players, rules, 1);
+        game.record_events(true);
+        game.round();
+        assert_eq!(bets(&mut game), [(0, 10.0), (1, 50.0)]);
+
+        let crossed = Rules {
+            min_bet: 50.0,
+            max_bet: 10.0,
+            ..Rules::default()
+        };
+        assert!(crossed.validate().is_err());
+        assert!(rules.validate().is_ok());
+    }
+
+    #[test]
+    fn short_stack() {
+        let mut player = player(0, Fixed(100.0));
+        player.balance = 25.0;
+        assert_eq!(player.bet(10.0, 50.0), Some(25.0));
+        assert_eq!(player.balance, 0.0);
+        player.balance = 5.0;
+        assert_eq!(player.bet(10.0, 50.0), None);
+        assert_eq!(player.balance, 5.0);
+    }
+
+    #[test]
+    fn seat_capacity() {
+        let rules = Rules {
+            seats: 2,
+            ..Rules::default()
+        };
+        let players = (0..3).map(|id| player(id, Fixed(10.0))).collect();
+        let mut game = Game::with_seed(players, rules, 2);
+        let seated: Vec<_> = game.players.iter().map(|p| p.seated).collect();
+        assert_eq!(seated, [true, true, false]);
+        assert_eq!(game.open_seats(), 0);
+
+        game.record_events(true);
+        game.round();
+        assert_eq!(bets(&mut game), [(0, 10.0), (1, 10.0)]);
+
+        // A player added to a full table watches until someone leaves
+        game.add_player(player(3, Fixed(10.0)));
+        assert!(!game.players[3].seated);
+        game.remove_player(0);
+        assert_eq!(game.open_seats(), 1);
+        game.round();
+        assert_eq!(bets(&mut game), [(1, 10.0), (2, 10.0)]);
+        assert_eq!(game.open_seats(), 0);
+    }
+
+    #[test]
+    fn mid_shoe_entry() {
+        let rules = Rules {
+            penetration: 0.5,
+            mid_shoe_entry: false,
+            ..Rules::default()
+        };
+        let mut game = Game::with_seed(vec![player(0, Fixed(10.0))], rules, 3);
+        game.record_events(true);
+        game.round();
+        game.take_events();
+        assert!(!game.can_join());
+
+        // The new player only sits down with the first round of a fresh shoe
+        game.add_player(player(1, Fixed(10.0)));
+        assert!(!game.players[1].seated);
+        loop {
+            game.round();
+            let events = game.take_events();
+            let shuffled = events.contains(&Event::Shuffle);
+            let joined = events
+                .iter()
+                .any(|e| matches!(e, Event::Bet { player: 1, .. }));
+            assert_eq!(joined, shuffled);
+            if shuffled {
+                break;
+            }
+        }
+        assert!(game.players[1].seated);
+    }
+
+    #[test]
+    fn wonging() {
+        use Face::*;
+        let cards = |faces: &[Face]| -> Vec<Card> {
+            faces
+                .iter()
+                .map(|&face| Card {
+                    face,
+                    suit: Suit::Spade,
+                })
+                .collect()
+        };
+        // Small cards in the first round push the count up, then tens bring it back to zero
+        let mut stack = cards(&[Two, Three, Four, Five, Six, Two]);
+        stack.extend(cards(&[Ten; 6]));
+        let rules = Rules::default();
+        let wong = WongingStrategy::new(
+            &rules,
+            CountingSystem::HI_LO,
+            2.0,
+            1.0,
+            Box::new(Fixed(10.0)),
+        );
+        let mut wonger = Player::new(0, 1000.0, Box::new(Stand), Box::new(wong));
+        wonger.seated = false;
+        let anchor = Player::new(1, 1000.0, Box::new(Stand), Box::new(Fixed(10.0)));
+        let mut game = Game::with_shoe(vec![wonger, anchor], rules, Shoe::stacked(stack, vec![]));
+        game.record_events(true);
+
+        let mut rounds: Vec<Vec<u8>> = vec![];
+        for _ in 0..3 {
+            game.round();
+            rounds.push(bets(&mut game).into_iter().map(|(id, _)| id).collect());
+        }
+        // Watches at a count of zero, joins at the entry count and leaves below the exit count
+        assert_eq!(rounds, [vec![1], vec![0, 1], vec![1]]);
+        assert!(!game.players[0].seated);
+    }
+
+    #[test]
+    fn recorded_true_count() {
+        use Face::*;
+        let stack = [Two, Three, Ten, Ten, Ten, Ten]
+            .map(|face| Card {
+                face,
+                suit: Suit::Spade,
+            })
+            .to_vec();
+        let rules = Rules::default();
+        let ramp = RampBettingStrategy::new(&rules, CountingSystem::HI_LO, 10.0, vec![1.0, 2.0]);
+        let players = vec![
+            Player::new(0, 1000.0, Box::new(Stand), Box::new(Fixed(10.0))),
+            Player::new(1, 1000.0, Box::new(Stand), Box::new(ramp)),
+        ];
+        let mut game = Game::with_shoe(players, rules, Shoe::stacked(stack, vec![]));
+        let outcomes = game.round();
+
+        // The second player bets after seeing the first player's cards, counting +2
+        let mut counter = Counter::new(CountingSystem::HI_LO);
+        counter.card_dealt(&Card {
+            face: Two,
+            suit: Suit::Spade,
+        });
+        counter.card_dealt(&Card {
+            face: Three,
+            suit: Suit::Spade,
+        });
+        assert_eq!(outcomes[0].true_count, 0.0);
+        assert_eq!(outcomes[1].true_count, counter.true_count(rules.decks));
+        assert_eq!(outcomes[1].bet, 20.0);
+    }
+}
@@ -1,59 +1,99 @@
 mod ai;
+pub mod analysis;
 mod cards;
+pub mod charts;
+pub mod events;
+mod experiment;
+pub mod export;
 mod game;
 mod player;
+mod replay;
+mod risk;
+mod rules;
+mod session;
+mod simulation;
+mod statistics;
+pub mod trainer;
 
-use std::collections::HashMap;
-use std::fmt::Write;
-use std::fs;
-use std::str::FromStr;
+use serde::{Deserialize, Serialize};
 
-pub use ai::action::{HumanActionStrategy, OptimalActionStrategy, SimpleActionStrategy};
-pub use ai::betting::{FlatBettingStrategy, HiLoCountingStrategy};
+pub use ai::action::{
+    ActionStrategy, HumanActionStrategy, OptimalActionStrategy, SimpleActionStrategy,
+};
+pub use ai::betting::{
+    BettingStrategy, EdgeEstimate, FlatBettingStrategy, HiLoCountingStrategy, HumanBettingStrategy,
+    KellyBettingStrategy, RampBettingStrategy, WongingStrategy,
+};
+pub use ai::chart::{Chart, ChartActionStrategy, ChartEntry, ParseChartError};
+pub use ai::count::{Counter, CountingSystem};
+pub use ai::progression::{
+    DAlembertStrategy, FibonacciStrategy, LabouchereStrategy, MartingaleStrategy,
+    OscarsGrindStrategy, ParoliStrategy,
+};
+pub use ai::registry::{Registry, StrategySpec};
+pub use cards::card::{Face, Suit};
+pub use cards::{Card, Hand};
+pub use experiment::{Experiment, RunSettings, Seat};
 pub use game::Game;
-pub use player::Player;
+pub use player::{Action, AvailableActions, Player, Seating};
+pub use replay::{Divergence, Replay, ReplayedRound};
+pub use risk::{
+    BankrollBands, SimulatedRuin, required_bankroll, risk_of_ruin, risk_of_ruin_within,
+};
+pub use rules::Rules;
+pub use session::{PlayerSummary, Session, SessionResult, StopCondition};
+pub use simulation::Simulation;
+pub use statistics::{CountStatistics, Statistics, Summary};
 
 /// Result of a single round of blackjack
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RoundResult {
     Blackjack,
     Win,
     Bust,
     Lose,
     Push,
+    Surrender,
 }
 
-pub fn print_round_results(results: &HashMap<RoundResult, i32>, num_rounds: i32) {
-    let wins =
-        *results.get(&RoundResult::Blackjack).unwrap() + *results.get(&RoundResult::Win).unwrap();
-    let win_percent = (wins as f32 / num_rounds as f32) * 100.;
-    let draws = *results.get(&RoundResult::Push).unwrap();
-    let draw_percent = (draws as f32 / num_rounds as f32) * 100.;
-    let losses =
-        *results.get(&RoundResult::Lose).unwrap() + *results.get(&RoundResult::Bust).unwrap();
-    let loss_percent = (losses as f32 / num_rounds as f32) * 100.;
-    println!(
-        "{num_rounds: <4} rounds: {win_percent: >2.0}% (W) {draw_percent: >2.0}% (D) {loss_percent: >3.0}% (L)",
-    );
+impl RoundResult {
+    pub const VARIANTS: [Self; 6] = [
+        Self::Blackjack,
+        Self::Win,
+        Self::Bust,
+        Self::Lose,
+        Self::Push,
+        Self::Surrender,
+    ];
 }
 
-pub fn save_results_to_csv(results: Vec<HashMap<RoundResult, i32>>) {
-    let mut contents = String::from_str("Wins,Draws,Losses,Total\n").unwrap();
-    for result in results {
-        let wins =
-            *result.get(&RoundResult::Blackjack).unwrap() + *result.get(&RoundResult::Win).unwrap();
-        let draws = *result.get(&RoundResult::Push).unwrap();
-        let losses =
-            *result.get(&RoundResult::Lose).unwrap() + *result.get(&RoundResult::Bust).unwrap();
-        writeln!(
-            contents,
-            "{},{},{},{}",
-            wins,
-            draws,
-            losses,
-            wins + draws + losses
-        )
-        .expect("infallible");
-    }
-    fs::write("results.csv", contents).unwrap()
+/// Everything that happened to a single hand, kept when a game is recording hands
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandRecord {
+    /// Number of the round in the game, starting from 1
+    pub round: usize,
+    pub outcome: HandOutcome,
+    /// The player's final cards
+    pub cards: Vec<Card>,
+    /// The dealer's final cards
+    pub dealer: Vec<Card>,
+    /// Decisions made on the hand, including those made before it was split off
+    pub actions: Vec<Action>,
+}
+
+/// Outcome of a single hand once the round has been settled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandOutcome {
+    /// Id of the player who played the hand
+    pub player: u8,
+    pub result: RoundResult,
+    /// Initial bet on the hand, before any double
+    pub bet: f32,
+    /// Total amount bet on the hand, including any double
+    pub wagered: f32,
+    /// Amount won, or negative if lost
+    pub net: f32,
+    /// True count the bet was made on, from the player's own count, or the table's count of
+    /// the cards shown so far in their counting system, or in Hi-Lo if they don't count
+    pub true_count: f32,
 }
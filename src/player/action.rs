@@ -1,6 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Action {
     Hit,
     Stand,
     Double,
     Split,
+    Surrender,
+}
+
+/// What a player does at the start of a round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seating {
+    /// Take a seat if not already seated, then bet on the round
+    Play,
+    /// Keep the current seat, or keep watching, without betting on the round
+    SitOut,
+    /// Give up the seat and watch the table
+    Leave,
+}
+
+/// Actions that may be taken on a hand, other than hitting and standing which are always allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailableActions {
+    pub double: bool,
+    pub split: bool,
+    pub surrender: bool,
 }
@@ -1,5 +1,5 @@
 mod action;
 mod player;
 
-pub use action::Action;
+pub use action::{Action, AvailableActions, Seating};
 pub use player::Player;
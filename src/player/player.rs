@@ -1,12 +1,18 @@
+use crate::HandOutcome;
 use crate::ai::action::ActionStrategy;
 use crate::ai::betting::BettingStrategy;
+use crate::ai::count::CountingSystem;
 use crate::cards::Card;
 use crate::cards::Hand;
-use crate::player::Action;
+use crate::player::{Action, AvailableActions, Seating};
 
 pub struct Player {
     pub id: u8,
+    /// Name shown in charts and results, `Player {id}` unless given
+    pub name: String,
     pub balance: f32,
+    /// Whether the player has a seat at the table, rather than watching from behind it
+    pub seated: bool,
     action_strategy: Box<dyn ActionStrategy>,
     betting_strategy: Box<dyn BettingStrategy>,
 }
@@ -20,7 +26,9 @@ impl Player {
     ) -> Self {
         Self {
             id,
+            name: format!("Player {id}"),
             balance,
+            seated: true,
             action_strategy,
             betting_strategy,
         }
@@ -34,13 +42,42 @@ impl Player {
         self.betting_strategy.shuffled();
     }
 
-    pub fn bet(&mut self) -> f32 {
-        let bet = self.betting_strategy.bet(self.balance);
+    pub fn result(&mut self, outcome: &HandOutcome) {
+        self.betting_strategy.result(outcome);
+    }
+
+    pub fn seating(&mut self) -> Seating {
+        self.betting_strategy
+            .seating(self.seated, &self.name, self.balance)
+    }
+
+    /// Counting system the player's betting strategy uses, if any
+    pub fn counting_system(&self) -> Option<CountingSystem> {
+        self.betting_strategy.counting_system()
+    }
+
+    /// True count the player's next bet is sized on, if their betting strategy keeps one
+    pub fn true_count(&self) -> Option<f32> {
+        self.betting_strategy.true_count()
+    }
+
+    /// Place a bet within the table limits and no more than the player's balance, taking it
+    /// from their balance. Returns `None` to sit the round out if they can't cover the minimum.
+    pub fn bet(&mut self, min_bet: f32, max_bet: f32) -> Option<f32> {
+        if self.balance < min_bet {
+            return None;
+        }
+        let bet = self
+            .betting_strategy
+            .bet(self.balance)
+            .min(max_bet)
+            .max(min_bet)
+            .min(self.balance);
         self.balance -= bet;
-        bet
+        Some(bet)
     }
 
-    pub fn action(&self, hand: &Hand, dealer_card: &Card) -> Action {
-        self.action_strategy.action(hand, dealer_card)
+    pub fn action(&self, hand: &Hand, dealer_card: &Card, available: &AvailableActions) -> Action {
+        self.action_strategy.action(hand, dealer_card, available)
     }
 }
@@ -0,0 +1,291 @@
+//! Replays a game from its recorded events, to step through it again or check that the game
+//! still plays out the same way.
+//!
+//! The shoe deals the recorded cards in order and shuffles where the recording did, while each
+//! player makes the recorded bets and decisions. Anything else, such as settlement and balances,
+//! is recomputed by the game and compared with the recording.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::HandOutcome;
+use crate::ai::action::ActionStrategy;
+use crate::ai::betting::BettingStrategy;
+use crate::cards::{Card, Hand, Shoe};
+use crate::events::{Event, Header};
+use crate::game::Game;
+use crate::player::{Action, AvailableActions, Player, Seating};
+
+/// Game driven by a recording, one round at a time
+pub struct Replay {
+    game: Game,
+    rounds: VecDeque<Vec<Event>>,
+}
+
+/// A round as it was recorded and as it was replayed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayedRound {
+    pub round: usize,
+    pub recorded: Vec<Event>,
+    pub replayed: Vec<Event>,
+    pub outcomes: Vec<HandOutcome>,
+}
+
+/// The first difference between a recorded round and its replay
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub round: usize,
+    /// Position of the differing event within the round
+    pub index: usize,
+    /// The recorded event, or `None` if the replay had more events
+    pub recorded: Option<Event>,
+    /// The replayed event, or `None` if the recording had more events
+    pub replayed: Option<Event>,
+}
+
+impl Replay {
+    /// Replay of `events`, which must have been recorded from the start of a game at the table
+    /// in `header`, with a player for each of its seats
+    pub fn new(header: &Header, events: &[Event]) -> Self {
+        let mut cards = vec![];
+        let mut shuffles = vec![];
+        let mut rounds = VecDeque::new();
+        for event in events {
+            match *event {
+                Event::Round { .. } => rounds.push_back(vec![]),
+                Event::Shuffle => shuffles.push(cards.len()),
+                Event::Burn { card } | Event::Deal { card, .. } | Event::DealerDraw { card } => {
+                    cards.push(card)
+                }
+                _ => {}
+            }
+            if let Some(round) = rounds.back_mut() {
+                round.push(event.clone());
+            }
+        }
+
+        let players = header
+            .seats
+            .iter()
+            .enumerate()
+            .map(|(id, seat)| {
+                let id = id as u8;
+                let bets = rounds.iter().map(|round| bet(round, id)).collect();
+                let actions = events
+                    .iter()
+                    .filter_map(|event| match *event {
+                        Event::Decision { player, action, .. } if player == id => Some(action),
+                        _ => None,
+                    })
+                    .collect();
+                let mut player = Player::new(
+                    id,
+                    seat.bankroll,
+                    Box::new(ScriptedActions(RefCell::new(actions))),
+                    Box::new(ScriptedBets { bets, next: 0.0 }),
+                );
+                player.name = seat.name();
+                player
+            })
+            .collect();
+
+        let shoe = Shoe::stacked(cards, shuffles);
+        Self {
+            game: Game::with_shoe(players, header.rules, shoe),
+            rounds,
+        }
+    }
+
+    /// The game being replayed, as of the last round stepped through
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn rounds_remaining(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// Replay the next recorded round, or `None` once every round has been replayed
+    pub fn step(&mut self) -> Option<ReplayedRound> {
+        let recorded = self.rounds.pop_front()?;
+        self.game.record_events(true);
+        let outcomes = self.game.round();
+        Some(ReplayedRound {
+            round: self.game.rounds_played(),
+            recorded,
+            replayed: self.game.take_events(),
+            outcomes,
+        })
+    }
+
+    /// Replay every remaining round, returning where each one diverged from the recording
+    pub fn divergences(&mut self) -> Vec<Divergence> {
+        std::iter::from_fn(|| self.step())
+            .filter_map(|round| round.divergence())
+            .collect()
+    }
+}
+
+impl ReplayedRound {
+    /// Where the replay first differs from the recording, if it does
+    pub fn divergence(&self) -> Option<Divergence> {
+        let length = self.recorded.len().max(self.replayed.len());
+        (0..length)
+            .find(|&i| self.recorded.get(i) != self.replayed.get(i))
+            .map(|index| Divergence {
+                round: self.round,
+                index,
+                recorded: self.recorded.get(index).cloned(),
+                replayed: self.replayed.get(index).cloned(),
+            })
+    }
+}
+
+/// Amount `player` bet in `round`, if they played it
+fn bet(round: &[Event], player: u8) -> Option<f32> {
+    round.iter().find_map(|event| match *event {
+        Event::Bet { player: id, amount } if id == player => Some(amount),
+        _ => None,
+    })
+}
+
+/// Makes each recorded decision in turn, standing once they run out or when a diverged replay
+/// no longer allows them
+struct ScriptedActions(RefCell<VecDeque<Action>>);
+
+impl ActionStrategy for ScriptedActions {
+    fn action(&self, _hand: &Hand, _dealer_card: &Card, available: &AvailableActions) -> Action {
+        let action = self.0.borrow_mut().pop_front().unwrap_or(Action::Stand);
+        let allowed = match action {
+            Action::Double => available.double,
+            Action::Split => available.split,
+            Action::Surrender => available.surrender,
+            Action::Hit | Action::Stand => true,
+        };
+        if allowed { action } else { Action::Stand }
+    }
+}
+
+/// Plays the rounds with a recorded bet, sitting out the rest
+struct ScriptedBets {
+    bets: VecDeque<Option<f32>>,
+    next: f32,
+}
+
+impl BettingStrategy for ScriptedBets {
+    fn bet(&mut self, _remaining: f32) -> f32 {
+        self.next
+    }
+
+    fn card_dealt(&mut self, _card: &Card) {}
+    fn shuffled(&mut self) {}
+
+    fn seating(&mut self, _seated: bool, _name: &str, _remaining: f32) -> Seating {
+        match self.bets.pop_front().flatten() {
+            Some(bet) => {
+                self.next = bet;
+                Seating::Play
+            }
+            None => Seating::SitOut,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ai::action::SimpleActionStrategy;
+    use crate::ai::betting::{FlatBettingStrategy, HiLoCountingStrategy};
+    use crate::ai::registry::StrategySpec;
+    use crate::experiment::Seat;
+    use crate::rules::Rules;
+    use crate::session::{Session, StopCondition};
+
+    use super::*;
+
+    #[test]
+    fn replay() {
+        let players = (0..3)
+            .map(|id| {
+                Player::new(
+                    id,
+                    500.0,
+                    Box::new(SimpleActionStrategy),
+                    Box::new(HiLoCountingStrategy::new()),
+                )
+            })
+            .collect();
+        let rules = Rules {
+            penetration: 0.75,
+            burn_cards: 1,
+            surrender: true,
+            ..Rules::default()
+        };
+        let mut session = Session::new(vec![StopCondition::Shoes(3)]);
+        session.record_events = true;
+        let mut game = Game::with_seed(players, rules, 11);
+        let result = session.play(&mut game);
+        let recorded = game.take_events();
+        let seat = Seat {
+            action: StrategySpec::new("simple"),
+            betting: StrategySpec::new("hilo"),
+            bankroll: 500.0,
+            name: None,
+        };
+        let header = Header {
+            rules,
+            seats: vec![seat; 3],
+        };
+
+        let mut replay = Replay::new(&header, &recorded);
+        assert_eq!(replay.rounds_remaining(), result.rounds);
+        assert_eq!(replay.divergences(), []);
+        assert_eq!(replay.game().shoes_completed(), 3);
+        for (replayed, recorded) in replay.game().players.iter().zip(&result.players) {
+            assert_eq!(replayed.balance, recorded.balance);
+        }
+
+        // A settlement that doesn't match what the game now pays is reported
+        let mut events = recorded.clone();
+        let index = events
+            .iter()
+            .rposition(|e| matches!(e, Event::Settle { .. }))
+            .unwrap();
+        if let Event::Settle { net, .. } = &mut events[index] {
+            *net += 1.0;
+        }
+        let divergences = Replay::new(&header, &events).divergences();
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].round, result.rounds);
+        assert_eq!(divergences[0].recorded.as_ref(), Some(&events[index]));
+    }
+
+    #[test]
+    fn dealt_out_shoe() {
+        // Without a cut card the shoe runs out part way through some rounds and between others
+        let rules = Rules::default();
+        let seat = Seat {
+            action: StrategySpec::new("simple"),
+            betting: StrategySpec::new("flat"),
+            bankroll: 1000.0,
+            name: None,
+        };
+        let header = Header {
+            rules,
+            seats: vec![seat],
+        };
+        for seed in 0..20 {
+            let player = Player::new(
+                0,
+                1000.0,
+                Box::new(SimpleActionStrategy),
+                Box::new(FlatBettingStrategy),
+            );
+            let mut session = Session::new(vec![StopCondition::Rounds(100)]);
+            session.record_events = true;
+            let mut game = Game::with_seed(vec![player], rules, seed);
+            session.play(&mut game);
+            let recorded = game.take_events();
+            assert_eq!(Replay::new(&header, &recorded).divergences(), [], "{seed}");
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Risk of ruin, the chance of losing an entire bankroll.
+//!
+//! The analytic estimates treat a player's results as a random walk with the measured EV and
+//! standard deviation per hand, which is accurate once bankrolls are many bets deep.
+
+use crate::session::SessionResult;
+
+/// Chance of ever losing `bankroll` when playing forever
+pub fn risk_of_ruin(ev: f64, sd: f64, bankroll: f64) -> f64 {
+    if ev <= 0.0 {
+        return 1.0;
+    }
+    if sd == 0.0 {
+        return 0.0;
+    }
+    (-2.0 * ev * bankroll / (sd * sd)).exp().min(1.0)
+}
+
+/// Chance of losing `bankroll` at some point within `hands`
+pub fn risk_of_ruin_within(ev: f64, sd: f64, bankroll: f64, hands: f64) -> f64 {
+    if bankroll <= 0.0 {
+        return 1.0;
+    }
+    if hands <= 0.0 {
+        return 0.0;
+    }
+    if sd == 0.0 {
+        return if ev * hands <= -bankroll { 1.0 } else { 0.0 };
+    }
+
+    // First passage of a Brownian motion with drift below -bankroll
+    let spread = sd * hands.sqrt();
+    let direct = normal_cdf((-bankroll - ev * hands) / spread);
+    let reflected =
+        (-2.0 * ev * bankroll / (sd * sd)).exp() * normal_cdf((-bankroll + ev * hands) / spread);
+    (direct + reflected).clamp(0.0, 1.0)
+}
+
+/// Bankroll needed to keep the risk of ruin when playing forever down to `target`, or `None`
+/// without a positive edge
+pub fn required_bankroll(ev: f64, sd: f64, target: f64) -> Option<f64> {
+    (ev > 0.0).then(|| -sd * sd * target.ln() / (2.0 * ev))
+}
+
+/// How often, and how quickly, a player went broke across simulated sessions
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedRuin {
+    pub sessions: usize,
+    pub ruined: usize,
+    /// Rounds played before going broke in each session that did, in ascending order
+    pub rounds_to_ruin: Vec<usize>,
+}
+
+impl SimulatedRuin {
+    /// Ruin of the player with `id` across `sessions`, where ruin is no longer being able to
+    /// make the minimum bet before the session stopped
+    pub fn new(sessions: &[SessionResult], id: u8) -> Self {
+        let mut rounds_to_ruin: Vec<_> = sessions
+            .iter()
+            .filter_map(|session| session.players.iter().find(|p| p.id == id)?.ruined)
+            .collect();
+        rounds_to_ruin.sort_unstable();
+
+        Self {
+            sessions: sessions.len(),
+            ruined: rounds_to_ruin.len(),
+            rounds_to_ruin,
+        }
+    }
+
+    /// Fraction of sessions ending in ruin
+    pub fn probability(&self) -> f64 {
+        self.ruined as f64 / self.sessions.max(1) as f64
+    }
+
+    pub fn mean_rounds_to_ruin(&self) -> Option<f64> {
+        let total: usize = self.rounds_to_ruin.iter().sum();
+        (self.ruined > 0).then(|| total as f64 / self.ruined as f64)
+    }
+
+    pub fn median_rounds_to_ruin(&self) -> Option<usize> {
+        self.rounds_to_ruin.get(self.ruined / 2).copied()
+    }
+}
+
+/// How a player's bankroll spread out over the rounds of many sessions
+#[derive(Debug, Clone, PartialEq)]
+pub struct BankrollBands {
+    /// Round of each point, starting from round 0 before any were played
+    pub rounds: Vec<usize>,
+    /// Bankroll at each of [`BankrollBands::PERCENTILES`] at each point
+    pub percentiles: Vec<[f32; 5]>,
+    /// Ruin within the sessions, which all stop by the last point
+    pub ruin: SimulatedRuin,
+}
+
+impl BankrollBands {
+    pub const PERCENTILES: [usize; 5] = [5, 25, 50, 75, 95];
+
+    /// Bands for the player with `id` across `sessions`, which must have recorded balances
+    /// every `interval` rounds. Sessions that stopped early keep their final balance.
+    pub fn new(sessions: &[SessionResult], id: u8, interval: usize) -> Self {
+        let players: Vec<_> = sessions
+            .iter()
+            .filter_map(|session| session.players.iter().find(|p| p.id == id))
+            .collect();
+        let points = players.iter().map(|p| p.balances.len()).max().unwrap_or(0);
+
+        let mut balances = Vec::with_capacity(players.len());
+        let percentiles = (0..=points)
+            .map(|point| {
+                balances.clear();
+                balances.extend(players.iter().map(|p| match point {
+                    0 => p.starting_balance,
+                    _ => p.balances.get(point - 1).copied().unwrap_or(p.balance),
+                }));
+                balances.sort_by(f32::total_cmp);
+                Self::PERCENTILES.map(|percentile| {
+                    let rank = percentile as f32 / 100.0 * balances.len().saturating_sub(1) as f32;
+                    balances.get(rank.round() as usize).copied().unwrap_or(0.0)
+                })
+            })
+            .collect();
+
+        Self {
+            rounds: (0..=points).map(|point| point * interval.max(1)).collect(),
+            percentiles,
+            ruin: SimulatedRuin::new(sessions, id),
+        }
+    }
+
+    /// Last round covered by the bands
+    pub fn horizon(&self) -> usize {
+        self.rounds.last().copied().unwrap_or(0)
+    }
+}
+
+/// Standard normal cumulative distribution function
+pub(crate) fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz and Stegun approximation 7.1.26, accurate to 1.5e-7
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let y = 1.0 - poly * (-x * x).exp();
+    y.copysign(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::ai::action::SimpleActionStrategy;
+    use crate::ai::betting::FlatBettingStrategy;
+    use crate::player::Player;
+    use crate::rules::Rules;
+    use crate::session::{PlayerSummary, Session, StopCondition};
+    use crate::simulation::Simulation;
+
+    use super::*;
+
+    #[test]
+    fn analytic() {
+        // 1% edge on 10 unit bets, with the usual 1.15 unit standard deviation
+        let (ev, sd) = (0.1, 11.5);
+        let bankroll = required_bankroll(ev, sd, 0.05).unwrap();
+        assert!((risk_of_ruin(ev, sd, bankroll) - 0.05).abs() < 1e-9);
+        assert!((bankroll - 1981.0).abs() < 1.0, "{bankroll}");
+
+        // Ruin within a horizon approaches ruin when playing forever
+        let short = risk_of_ruin_within(ev, sd, bankroll, 1_000.0);
+        let long = risk_of_ruin_within(ev, sd, bankroll, 1e8);
+        assert!(short < long);
+        assert!((long - 0.05).abs() < 1e-6);
+
+        assert_eq!(risk_of_ruin(-0.05, sd, bankroll), 1.0);
+        assert_eq!(required_bankroll(-0.05, sd, 0.05), None);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+    }
+
+    #[test]
+    fn simulated() {
+        let session = |ruined: &[(u8, Option<usize>)]| SessionResult {
+            stopped: StopCondition::Rounds(100),
+            rounds: 100,
+            shoes: 1,
+            duration: Duration::ZERO,
+            players: ruined
+                .iter()
+                .map(|&(id, ruined)| PlayerSummary {
+                    ruined,
+                    ..PlayerSummary::new(id, 100.0)
+                })
+                .collect(),
+        };
+        let sessions = [
+            session(&[(0, Some(40)), (1, None)]),
+            session(&[(0, None), (1, Some(5))]),
+            session(&[(0, Some(10))]),
+            session(&[(0, Some(25)), (1, Some(90))]),
+            session(&[(0, None)]),
+        ];
+
+        let ruin = SimulatedRuin::new(&sessions, 0);
+        assert_eq!(ruin.sessions, 5);
+        assert_eq!(ruin.ruined, 3);
+        assert_eq!(ruin.rounds_to_ruin, [10, 25, 40]);
+        assert!((ruin.probability() - 0.6).abs() < 1e-9);
+        assert_eq!(ruin.mean_rounds_to_ruin(), Some(25.0));
+        assert_eq!(ruin.median_rounds_to_ruin(), Some(25));
+
+        let other = SimulatedRuin::new(&sessions, 1);
+        assert_eq!(other.rounds_to_ruin, [5, 90]);
+        assert!((other.probability() - 0.4).abs() < 1e-9);
+        assert_eq!(other.mean_rounds_to_ruin(), Some(47.5));
+
+        let none = SimulatedRuin::new(&[], 0);
+        assert_eq!(none.probability(), 0.0);
+        assert_eq!(none.mean_rounds_to_ruin(), None);
+        assert_eq!(none.median_rounds_to_ruin(), None);
+    }
+
+    #[test]
+    fn bands() {
+        let mut simulation = Simulation::new(Rules::default(), 50);
+        simulation.seed = 2;
+        let mut session = Session::new(vec![StopCondition::Rounds(200)]);
+        session.balance_interval = Some(20);
+        let player = || {
+            vec![Player::new(
+                0,
+                100.0,
+                Box::new(SimpleActionStrategy),
+                Box::new(FlatBettingStrategy),
+            )]
+        };
+        let sessions = simulation.run_sessions(player, &session);
+
+        let bands = BankrollBands::new(&sessions, 0, 20);
+        assert_eq!(bands.horizon(), 200);
+        assert_eq!(bands.rounds.len(), 11);
+        assert_eq!(bands.percentiles[0], [100.0; 5]);
+        assert!(bands.percentiles.iter().all(|p| p.is_sorted()));
+        assert!(bands.percentiles[10][0] < bands.percentiles[10][4]);
+        assert_eq!(bands.ruin.sessions, 50);
+    }
+}
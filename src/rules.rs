@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// House rules for a blackjack table
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules {
+    /// Number of 52 card decks in the shoe
+    pub decks: u8,
+    /// Fraction of the shoe dealt before the cut card is reached and it is reshuffled between
+    /// rounds. At 1.0 the shoe is only reshuffled once it runs out, even part way through a round
+    pub penetration: f32,
+    /// Dealer hits soft 17 (H17) instead of standing on all 17s (S17)
+    pub dealer_hits_soft_17: bool,
+    /// Payout ratio for a natural blackjack, e.g. 1.5 for 3:2
+    pub blackjack_payout: f32,
+    /// Doubling is allowed on a hand created by a split
+    pub double_after_split: bool,
+    /// Dealer checks for blackjack before players act when showing an ace or ten.
+    /// Without a peek, a dealer blackjack takes all doubled and split bets as well
+    pub dealer_peeks: bool,
+    /// Players may surrender their first two cards for half of their bet back
+    pub surrender: bool,
+    /// Smallest bet accepted at the table, players who can't afford it sit out
+    pub min_bet: f32,
+    /// Largest bet accepted at the table
+    pub max_bet: f32,
+    /// Number of seats at the table
+    pub seats: usize,
+    /// Players may take a seat part way through a shoe, rather than only after a shuffle
+    pub mid_shoe_entry: bool,
+    /// Cards discarded unseen from the top of the shoe after each shuffle
+    pub burn_cards: u8,
+}
+
+impl Rules {
+    /// Check the rules describe a table that can be played at
+    pub fn validate(&self) -> Result<(), String> {
+        if self.decks == 0 {
+            return Err("the shoe needs at least one deck".to_string());
+        }
+        if !(self.penetration > 0.0 && self.penetration <= 1.0) {
+            return Err(format!(
+                "the penetration {} must be above 0 and no more than 1",
+                self.penetration
+            ));
+        }
+        if !(self.blackjack_payout > 0.0 && self.blackjack_payout.is_finite()) {
+            return Err(format!(
+                "the blackjack payout {} must be above 0",
+                self.blackjack_payout
+            ));
+        }
+        if self.seats == 0 {
+            return Err("the table needs at least one seat".to_string());
+        }
+        if self.burn_cards as u32 >= self.decks as u32 * 52 {
+            return Err(format!(
+                "burning {} cards would leave nothing to deal from {} decks",
+                self.burn_cards, self.decks
+            ));
+        }
+        if !(self.min_bet >= 0.0 && self.min_bet <= self.max_bet) {
+            return Err(format!(
+                "the minimum bet ${} must be no more than the maximum bet ${}",
+                self.min_bet, self.max_bet
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            decks: 1,
+            penetration: 1.0,
+            dealer_hits_soft_17: false,
+            blackjack_payout: 1.5,
+            double_after_split: true,
+            dealer_peeks: false,
+            surrender: false,
+            min_bet: 10.0,
+            max_bet: f32::MAX,
+            seats: 7,
+            mid_shoe_entry: true,
+            burn_cards: 0,
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+
+use crate::game::Game;
+use crate::statistics::{CountStatistics, Statistics};
+use crate::{HandOutcome, RoundResult};
+
+/// Condition that ends a session once it is met
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopCondition {
+    /// No player has enough left to make the minimum bet. Always checked, so sessions can't
+    /// continue without anyone playing.
+    Bankrupt,
+    /// Every player has either reached this balance or can no longer make the minimum bet
+    Goal(f32),
+    /// A number of rounds have been played
+    Rounds(usize),
+    /// The session has been running for this long. Unlike the other conditions, this depends
+    /// on how fast the session runs, so isn't reproducible from a seed.
+    Time(Duration),
+    /// A number of shoes have been dealt out and reshuffled
+    Shoes(usize),
+}
+
+/// Results for a single player over a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerSummary {
+    pub id: u8,
+    pub starting_balance: f32,
+    pub balance: f32,
+    /// Hands played, one for each initial bet with the hands of a split counted together, the
+    /// same as [`Statistics::hands`]
+    pub hands: i32,
+    /// Hands won overall, by their net result across the hands of any split
+    pub wins: i32,
+    pub pushes: i32,
+    pub losses: i32,
+    /// Number of times each result was settled, where every hand of a split has its own result
+    pub results: HashMap<RoundResult, i32>,
+    /// Total amount bet across every hand
+    pub wagered: f32,
+    pub statistics: Statistics,
+    /// The same statistics grouped by the true count at the time of each bet
+    pub by_count: CountStatistics,
+    /// Round after which the player could no longer make the minimum bet, if they went broke
+    pub ruined: Option<usize>,
+    /// Balance every [`Session::balance_interval`] rounds, if the session was recording them
+    pub balances: Vec<f32>,
+}
+
+impl PlayerSummary {
+    pub(crate) fn new(id: u8, balance: f32) -> Self {
+        Self {
+            id,
+            starting_balance: balance,
+            balance,
+            hands: 0,
+            wins: 0,
+            pushes: 0,
+            losses: 0,
+            results: RoundResult::VARIANTS.map(|result| (result, 0)).into(),
+            wagered: 0.0,
+            statistics: Statistics::default(),
+            by_count: CountStatistics::default(),
+            ruined: None,
+            balances: vec![],
+        }
+    }
+
+    /// Record every hand played from a single initial bet
+    fn record(&mut self, outcomes: &[HandOutcome]) {
+        if outcomes.is_empty() {
+            return;
+        }
+        let mut net = 0.0;
+        for outcome in outcomes {
+            *self.results.get_mut(&outcome.result).unwrap() += 1;
+            self.wagered += outcome.wagered;
+            net += outcome.net;
+        }
+        self.hands += 1;
+        if net > 0.0 {
+            self.wins += 1;
+        } else if net < 0.0 {
+            self.losses += 1;
+        } else {
+            self.pushes += 1;
+        }
+    }
+
+    /// Amount won over the session, or negative if lost
+    pub fn net(&self) -> f32 {
+        self.balance - self.starting_balance
+    }
+}
+
+/// Summary of a session once it has stopped
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionResult {
+    /// The condition that ended the session
+    pub stopped: StopCondition,
+    pub rounds: usize,
+    pub shoes: usize,
+    pub duration: Duration,
+    /// Summary for each player that started the session, in seat order
+    pub players: Vec<PlayerSummary>,
+}
+
+/// Plays rounds of a game until one of its stop conditions is met
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub stop: Vec<StopCondition>,
+    /// Have the game keep a record of the hands of each round, to be taken from it after the
+    /// round by [`Session::play_with`]
+    pub record_hands: bool,
+    /// Have the game record every event at the table, to be taken from it after each round by
+    /// [`Session::play_with`]
+    pub record_events: bool,
+    /// Record each player's balance after every this many rounds
+    pub balance_interval: Option<usize>,
+}
+
+impl Session {
+    /// Rounds a session stops after if it has no other limit on its rounds, shoes or time
+    pub const ROUND_LIMIT: usize = 100_000;
+
+    /// Session stopping on any of `stop`, or after [`Session::ROUND_LIMIT`] rounds if none of
+    /// them limits the rounds, shoes or time. Players who sit out, such as a back counter
+    /// waiting for the count, may otherwise never go broke or reach a goal.
+    pub fn new(mut stop: Vec<StopCondition>) -> Self {
+        let limited = stop.iter().any(|condition| {
+            matches!(
+                condition,
+                StopCondition::Rounds(_) | StopCondition::Shoes(_) | StopCondition::Time(_)
+            )
+        });
+        if !limited {
+            stop.push(StopCondition::Rounds(Self::ROUND_LIMIT));
+        }
+        Self {
+            stop,
+            record_hands: false,
+            record_events: false,
+            balance_interval: None,
+        }
+    }
+
+    /// Play the session to completion, accumulating the results of every hand
+    pub fn play(&self, game: &mut Game) -> SessionResult {
+        let Ok(result) = self.play_with(game, |_| Ok::<_, Infallible>(()));
+        result
+    }
+
+    /// Play the session to completion, calling `after_round` once each round is settled. The
+    /// hands and events of the round can be written out there as the session goes, rather than
+    /// kept until it ends. Stops at the first error `after_round` returns.
+    pub fn play_with<E>(
+        &self,
+        game: &mut Game,
+        mut after_round: impl FnMut(&mut Game) -> Result<(), E>,
+    ) -> Result<SessionResult, E> {
+        let start = Instant::now();
+        let shoes = game.shoes_completed();
+        let mut players: Vec<_> = game
+            .players
+            .iter()
+            .map(|p| PlayerSummary::new(p.id, p.balance))
+            .collect();
+
+        let min_bet = game.rules().min_bet;
+        let mut rounds = 0;
+        game.record_hands(self.record_hands);
+        game.record_events(self.record_events);
+        let stopped = loop {
+            let elapsed = start.elapsed();
+            if let Some(condition) =
+                self.check(game, rounds, game.shoes_completed() - shoes, elapsed)
+            {
+                break condition;
+            }
+
+            let outcomes = game.round();
+            for player in &mut players {
+                let hands: Vec<_> = outcomes
+                    .iter()
+                    .filter(|o| o.player == player.id)
+                    .copied()
+                    .collect();
+                player.record(&hands);
+                player.statistics.record(&hands);
+                player.by_count.record(&hands);
+            }
+            rounds += 1;
+
+            for player in &mut players {
+                if let Some(p) = game.players.iter().find(|p| p.id == player.id) {
+                    player.balance = p.balance;
+                }
+                if player.balance < min_bet && player.ruined.is_none() {
+                    player.ruined = Some(rounds);
+                }
+                if self
+                    .balance_interval
+                    .is_some_and(|interval| rounds % interval.max(1) == 0)
+                {
+                    player.balances.push(player.balance);
+                }
+            }
+            after_round(game)?;
+        };
+
+        Ok(SessionResult {
+            stopped,
+            rounds,
+            shoes: game.shoes_completed() - shoes,
+            duration: start.elapsed(),
+            players,
+        })
+    }
+
+    /// The first condition that has been met, if any
+    fn check(
+        &self,
+        game: &Game,
+        rounds: usize,
+        shoes: usize,
+        elapsed: Duration,
+    ) -> Option<StopCondition> {
+        let min_bet = game.rules().min_bet;
+        if game.players.iter().all(|p| p.balance < min_bet) {
+            return Some(StopCondition::Bankrupt);
+        }
+
+        self.stop
+            .iter()
+            .copied()
+            .find(|condition| match *condition {
+                StopCondition::Bankrupt => false,
+                StopCondition::Goal(goal) => game
+                    .players
+                    .iter()
+                    .all(|p| p.balance >= goal || p.balance < min_bet),
+                StopCondition::Rounds(max) => rounds >= max,
+                StopCondition::Time(max) => elapsed >= max,
+                StopCondition::Shoes(max) => shoes >= max,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ai::action::SimpleActionStrategy;
+    use crate::ai::betting::{FlatBettingStrategy, WongingStrategy};
+    use crate::ai::count::CountingSystem;
+    use crate::player::Player;
+    use crate::rules::Rules;
+
+    use super::*;
+
+    fn game(balance: f32) -> Game {
+        let player = Player::new(
+            0,
+            balance,
+            Box::new(SimpleActionStrategy),
+            Box::new(FlatBettingStrategy),
+        );
+        Game::with_seed(vec![player], Rules::default(), 7)
+    }
+
+    #[test]
+    fn stop_conditions() {
+        let result = Session::new(vec![StopCondition::Rounds(25)]).play(&mut game(10_000.0));
+        assert_eq!(result.stopped, StopCondition::Rounds(25));
+        assert_eq!(result.rounds, 25);
+        let player = &result.players[0];
+        assert_eq!(player.wins + player.pushes + player.losses, player.hands);
+        assert_eq!(player.hands as u64, player.statistics.hands());
+        assert!(player.results.values().sum::<i32>() >= player.hands);
+        assert_eq!(player.hands, 25);
+
+        let result = Session::new(vec![StopCondition::Shoes(2)]).play(&mut game(10_000.0));
+        assert_eq!(result.stopped, StopCondition::Shoes(2));
+        assert_eq!(result.shoes, 2);
+
+        let result = Session::new(vec![
+            StopCondition::Goal(200.0),
+            StopCondition::Rounds(1_000_000),
+        ])
+        .play(&mut game(100.0));
+        let balance = result.players[0].balance;
+        match result.stopped {
+            StopCondition::Goal(_) => assert!(balance >= 200.0),
+            StopCondition::Bankrupt => assert!(balance < 10.0),
+            stopped => panic!("Unexpected stop: {stopped:?}"),
+        }
+    }
+
+    #[test]
+    fn limited() {
+        // Going broke or reaching a goal alone still has a limit on the rounds
+        let stop = vec![StopCondition::Bankrupt, StopCondition::Goal(200.0)];
+        assert_eq!(
+            Session::new(stop).stop.last(),
+            Some(&StopCondition::Rounds(Session::ROUND_LIMIT))
+        );
+
+        // A back counter waiting for a count that never comes still stops at the limit
+        let rules = Rules::default();
+        let wong = WongingStrategy::new(
+            &rules,
+            CountingSystem::HI_LO,
+            100.0,
+            0.0,
+            Box::new(FlatBettingStrategy),
+        );
+        let mut player = Player::new(0, 100.0, Box::new(SimpleActionStrategy), Box::new(wong));
+        player.seated = false;
+        let mut game = Game::with_seed(vec![player], rules, 1);
+        let stop = vec![StopCondition::Goal(200.0), StopCondition::Shoes(3)];
+        let result = Session::new(stop).play(&mut game);
+        assert_eq!(result.stopped, StopCondition::Shoes(3));
+        assert_eq!(result.players[0].hands, 0);
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::game::Game;
+use crate::player::Player;
+use crate::rules::Rules;
+use crate::session::{Session, SessionResult};
+
+/// Runs many independent sessions of blackjack across multiple threads.
+///
+/// Every session plays a new game seeded from the master seed and the session's index, so the
+/// results are the same no matter how many threads are used.
+pub struct Simulation {
+    pub rules: Rules,
+    pub sessions: usize,
+    pub seed: u64,
+    pub threads: usize,
+}
+
+impl Simulation {
+    /// Simulation with a random seed, using every available core
+    pub fn new(rules: Rules, sessions: usize) -> Self {
+        Self {
+            rules,
+            sessions,
+            seed: rand::random(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    /// Play every session, returning each session's result in order.
+    ///
+    /// `players` creates the players for a new session, while `session` plays the session with
+    /// the given index to completion and summarises it.
+    pub fn run<T: Send>(
+        &self,
+        players: impl Fn() -> Vec<Player> + Sync,
+        session: impl Fn(usize, &mut Game) -> T + Sync,
+    ) -> Vec<T> {
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(self.sessions));
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                scope.spawn(|| {
+                    let mut completed = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        if index >= self.sessions {
+                            break;
+                        }
+                        let seed = session_seed(self.seed, index);
+                        let mut game = Game::with_seed(players(), self.rules, seed);
+                        completed.push((index, session(index, &mut game)));
+                    }
+                    results.lock().unwrap().append(&mut completed);
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Play every session until it stops, returning each session's result in order
+    pub fn run_sessions(
+        &self,
+        players: impl Fn() -> Vec<Player> + Sync,
+        session: &Session,
+    ) -> Vec<SessionResult> {
+        self.run(players, |_, game| session.play(game))
+    }
+}
+
+/// Seed for the session at `index`, mixed so that simulations with nearby master seeds don't
+/// play the same games
+fn session_seed(seed: u64, index: usize) -> u64 {
+    splitmix64(splitmix64(seed).wrapping_add(index as u64))
+}
+
+/// The SplitMix64 finaliser, which spreads nearby inputs across the whole range
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ai::action::SimpleActionStrategy;
+    use crate::ai::betting::HiLoCountingStrategy;
+
+    use super::*;
+
+    #[test]
+    fn independent_of_threads() {
+        let players = || {
+            vec![Player::new(
+                0,
+                100.0,
+                Box::new(SimpleActionStrategy),
+                Box::new(HiLoCountingStrategy::new()),
+            )]
+        };
+        let session = |_, game: &mut Game| {
+            (0..50).for_each(|_| {
+                game.round();
+            });
+            game.players[0].balance
+        };
+
+        let mut simulation = Simulation::new(Rules::default(), 40);
+        simulation.threads = 1;
+        let serial = simulation.run(players, session);
+        simulation.threads = 4;
+        let parallel = simulation.run(players, session);
+
+        assert_eq!(serial, parallel);
+        assert!(serial.iter().any(|&balance| balance != serial[0]));
+
+        // A neighbouring master seed plays different games rather than the same ones shifted
+        simulation.seed = simulation.seed.wrapping_add(1);
+        let shifted = simulation.run(players, session);
+        assert_ne!(shifted[..39], serial[1..]);
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use crate::HandOutcome;
+
+/// z score for a 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// Accumulates the net result of each of a player's hands.
+///
+/// A hand is everything played from a single initial bet, so the hands of a split count
+/// together. Statistics from separate sessions can be combined with [`Statistics::merge`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Statistics {
+    hands: u64,
+    /// Mean net result per hand
+    mean: f64,
+    /// Sum of squared differences from the mean
+    m2: f64,
+    net: f64,
+    initial_bets: f64,
+    action: f64,
+}
+
+/// A player's results grouped by the true count when each bet was made, with counts floored to
+/// whole numbers so that e.g. +2 holds every count from +2 up to +3
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CountStatistics {
+    buckets: BTreeMap<i32, Statistics>,
+}
+
+/// Summary of a player's results, in the same currency as their bets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub hands: u64,
+    pub ev_per_hand: f64,
+    /// Fraction of the initial bet won per hand, e.g. 0.01 for a 1% edge
+    pub ev_per_initial_bet: f64,
+    pub sd_per_hand: f64,
+    /// 95% confidence interval of the EV per hand
+    pub ev_per_hand_ci: (f64, f64),
+    /// 95% confidence interval of the EV per initial bet
+    pub ev_per_initial_bet_ci: (f64, f64),
+    pub average_bet: f64,
+    /// Total amount wagered, including doubles and splits
+    pub total_action: f64,
+    pub hands_per_hour: f64,
+    pub ev_per_hour: f64,
+    pub sd_per_hour: f64,
+}
+
+impl Statistics {
+    /// Record every hand played from a single initial bet
+    pub fn record(&mut self, outcomes: &[HandOutcome]) {
+        let Some(first) = outcomes.first() else {
+            return;
+        };
+        let net: f32 = outcomes.iter().map(|o| o.net).sum();
+        let wagered: f32 = outcomes.iter().map(|o| o.wagered).sum();
+
+        self.hands += 1;
+        let net = net as f64;
+        let delta = net - self.mean;
+        self.mean += delta / self.hands as f64;
+        self.m2 += delta * (net - self.mean);
+        self.net += net;
+        self.initial_bets += first.bet as f64;
+        self.action += wagered as f64;
+    }
+
+    /// Combine with statistics from another set of hands
+    pub fn merge(&mut self, other: &Statistics) {
+        if other.hands == 0 {
+            return;
+        }
+        let hands = self.hands + other.hands;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.hands as f64 / hands as f64;
+        self.m2 += other.m2 + delta * delta * (self.hands * other.hands) as f64 / hands as f64;
+        self.hands = hands;
+        self.net += other.net;
+        self.initial_bets += other.initial_bets;
+        self.action += other.action;
+    }
+
+    /// Hands played, one for each initial bet with the hands of a split counted together
+    pub fn hands(&self) -> u64 {
+        self.hands
+    }
+
+    /// Total amount won, or negative if lost
+    pub fn net(&self) -> f64 {
+        self.net
+    }
+
+    /// Sample variance of the net result per hand
+    pub fn variance(&self) -> f64 {
+        if self.hands < 2 {
+            0.0
+        } else {
+            self.m2 / (self.hands - 1) as f64
+        }
+    }
+
+    /// Summarise the results, assuming `hands_per_hour` for the hourly figures
+    pub fn summary(&self, hands_per_hour: f64) -> Summary {
+        let hands = self.hands.max(1) as f64;
+        let sd = self.variance().sqrt();
+        let error = Z_95 * sd / hands.sqrt();
+        let average_bet = self.initial_bets / hands;
+        let per_bet = |value: f64| {
+            if average_bet > 0.0 {
+                value / average_bet
+            } else {
+                0.0
+            }
+        };
+        let ev_per_initial_bet = per_bet(self.mean);
+
+        Summary {
+            hands: self.hands,
+            ev_per_hand: self.mean,
+            ev_per_initial_bet,
+            sd_per_hand: sd,
+            ev_per_hand_ci: (self.mean - error, self.mean + error),
+            ev_per_initial_bet_ci: (
+                ev_per_initial_bet - per_bet(error),
+                ev_per_initial_bet + per_bet(error),
+            ),
+            average_bet,
+            total_action: self.action,
+            hands_per_hour,
+            ev_per_hour: self.mean * hands_per_hour,
+            sd_per_hour: sd * hands_per_hour.sqrt(),
+        }
+    }
+}
+
+impl CountStatistics {
+    /// Record every hand played from a single initial bet
+    pub fn record(&mut self, outcomes: &[HandOutcome]) {
+        let Some(first) = outcomes.first() else {
+            return;
+        };
+        let bucket = first.true_count.floor() as i32;
+        self.buckets.entry(bucket).or_default().record(outcomes);
+    }
+
+    /// Combine with statistics from another set of hands
+    pub fn merge(&mut self, other: &CountStatistics) {
+        for (&bucket, statistics) in &other.buckets {
+            self.buckets.entry(bucket).or_default().merge(statistics);
+        }
+    }
+
+    /// Statistics for each true count that was played at, from lowest to highest
+    pub fn buckets(&self) -> impl Iterator<Item = (i32, &Statistics)> {
+        self.buckets
+            .iter()
+            .map(|(&bucket, statistics)| (bucket, statistics))
+    }
+
+    pub fn hands(&self) -> u64 {
+        self.buckets.values().map(Statistics::hands).sum()
+    }
+}
+
+impl Display for CountStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.hands().max(1) as f64;
+        writeln!(
+            f,
+            "{:>4} {:>10} {:>12} {:>12} {:>10}",
+            "TC", "Frequency", "Average bet", "EV per hand", "Variance"
+        )?;
+        for (bucket, statistics) in self.buckets() {
+            let summary = statistics.summary(0.0);
+            writeln!(
+                f,
+                "{bucket:>+4} {:>9.2}% {:>12.2} {:>+12.4} {:>10.2}",
+                statistics.hands() as f64 / total * 100.0,
+                summary.average_bet,
+                summary.ev_per_hand,
+                statistics.variance()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Summary {
+    /// Desirability index, 1000 times the ratio of EV to standard deviation. Comparable
+    /// between players regardless of their bet sizes.
+    pub fn desirability_index(&self) -> f64 {
+        if self.sd_per_hand > 0.0 {
+            1000.0 * self.ev_per_hand / self.sd_per_hand
+        } else {
+            0.0
+        }
+    }
+
+    /// Standardised win rate, the EV per 100 hands of a 10,000 unit bankroll when bets are
+    /// scaled to it for a 13.5% risk of ruin. Zero without an edge.
+    pub fn score(&self) -> f64 {
+        self.desirability_index().max(0.0).powi(2)
+    }
+
+    /// Hands needed for the expected win to equal one standard deviation of results, so that
+    /// skill starts to outweigh luck. Infinite without an edge.
+    pub fn n0(&self) -> f64 {
+        if self.ev_per_hand > 0.0 {
+            (self.sd_per_hand / self.ev_per_hand).powi(2)
+        } else {
+            f64::INFINITY
+        }
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (low, high) = self.ev_per_hand_ci;
+        let (low_pct, high_pct) = self.ev_per_initial_bet_ci;
+        writeln!(f, "Hands               {}", self.hands)?;
+        writeln!(
+            f,
+            "EV per hand         {:+.4} (95% CI {low:+.4} to {high:+.4})",
+            self.ev_per_hand
+        )?;
+        writeln!(
+            f,
+            "EV per initial bet  {:+.3}% (95% CI {:+.3}% to {:+.3}%)",
+            self.ev_per_initial_bet * 100.0,
+            low_pct * 100.0,
+            high_pct * 100.0
+        )?;
+        writeln!(f, "SD per hand         {:.4}", self.sd_per_hand)?;
+        writeln!(f, "Average bet         {:.2}", self.average_bet)?;
+        writeln!(f, "Total action        {:.2}", self.total_action)?;
+        writeln!(
+            f,
+            "DI / SCORE / N0     {:.2} / {:.2} / {:.0}",
+            self.desirability_index(),
+            self.score(),
+            self.n0()
+        )?;
+        writeln!(
+            f,
+            "Per hour            {:+.2} EV, {:.2} SD at {} hands per hour",
+            self.ev_per_hour, self.sd_per_hour, self.hands_per_hour
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RoundResult;
+
+    use super::*;
+
+    fn hand(bet: f32, wagered: f32, net: f32) -> HandOutcome {
+        HandOutcome {
+            player: 0,
+            result: RoundResult::Win,
+            bet,
+            wagered,
+            net,
+            true_count: 0.0,
+        }
+    }
+
+    #[test]
+    fn summary() {
+        let nets = [10.0, -10.0, 20.0, -10.0, 10.0, 15.0];
+        let mut all = Statistics::default();
+        let mut first = Statistics::default();
+        let mut second = Statistics::default();
+        for (i, &net) in nets.iter().enumerate() {
+            let outcome = [hand(10.0, 10.0, net)];
+            all.record(&outcome);
+            if i < 2 { &mut first } else { &mut second }.record(&outcome);
+        }
+        // A split counts as a single hand
+        let split = [hand(10.0, 20.0, 20.0), hand(10.0, 10.0, -10.0)];
+        all.record(&split);
+        second.record(&split);
+        first.merge(&second);
+
+        let summary = all.summary(100.0);
+        assert_eq!(summary.hands, 7);
+        assert!((summary.ev_per_hand - 45.0 / 7.0).abs() < 1e-9);
+        assert!((summary.ev_per_initial_bet - 4.5 / 7.0).abs() < 1e-9);
+        assert!((summary.sd_per_hand - 11.8019).abs() < 1e-4);
+        assert_eq!(summary.total_action, 90.0);
+        let (low, high) = summary.ev_per_hand_ci;
+        assert!((high - low - 2.0 * 1.96 * 11.8019 / 7f64.sqrt()).abs() < 1e-3);
+        assert!((summary.n0() - (11.8019f64 * 7.0 / 45.0).powi(2)).abs() < 1e-2);
+        assert!((summary.score() - 1e6 / summary.n0()).abs() < 1e-6);
+
+        let merged = first.summary(100.0);
+        assert_eq!(merged.hands, summary.hands);
+        assert!((merged.sd_per_hand - summary.sd_per_hand).abs() < 1e-9);
+    }
+
+    #[test]
+    fn count_buckets() {
+        let mut first = CountStatistics::default();
+        let mut second = CountStatistics::default();
+        for (true_count, net) in [(-0.5, -10.0), (0.0, 10.0), (0.9, -10.0), (2.5, 20.0)] {
+            first.record(&[HandOutcome {
+                true_count,
+                ..hand(10.0, 10.0, net)
+            }]);
+        }
+        second.record(&[HandOutcome {
+            true_count: 2.0,
+            ..hand(20.0, 20.0, 20.0)
+        }]);
+        first.merge(&second);
+
+        let buckets: Vec<_> = first
+            .buckets()
+            .map(|(bucket, statistics)| (bucket, statistics.hands(), statistics.net()))
+            .collect();
+        assert_eq!(buckets, [(-1, 1, -10.0), (0, 2, 0.0), (2, 2, 40.0)]);
+        assert_eq!(first.hands(), 5);
+    }
+}
@@ -0,0 +1,324 @@
+//! Practice for human players, grading every decision against a reference strategy and drilling
+//! the count.
+//!
+//! Decisions are graded with the exact expected values from [`Analyzer`], so each mistake comes
+//! with what it cost in initial bets.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::ai::count::{Counter, CountingSystem};
+use crate::analysis::composition::rank;
+use crate::analysis::{ActionValues, Analyzer, Composition};
+use crate::cards::{Card, Hand};
+use crate::player::{Action, AvailableActions};
+use crate::rules::Rules;
+
+/// How far a true count estimate may be from the exact true count and still be right, since
+/// players estimate the decks left by eye
+pub const TRUE_COUNT_TOLERANCE: f32 = 0.5;
+
+/// Strategy that decisions are graded against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reference {
+    /// The best play against a full shoe, without the hand and the dealer's upcard
+    Basic,
+    /// The best play against the cards that haven't been seen since the shuffle, which
+    /// includes every deviation a count would suggest
+    Count,
+}
+
+/// Kind of hand a decision was made on, to keep accuracy for each separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandCategory {
+    Hard,
+    Soft,
+    Pair,
+}
+
+impl HandCategory {
+    pub fn of(hand: &Hand, available: &AvailableActions) -> Self {
+        if available.split {
+            HandCategory::Pair
+        } else if hand.is_soft() {
+            HandCategory::Soft
+        } else {
+            HandCategory::Hard
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandCategory::Hard => "Hard totals",
+            HandCategory::Soft => "Soft totals",
+            HandCategory::Pair => "Pairs",
+        }
+    }
+}
+
+/// A decision compared with the reference strategy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grade {
+    pub category: HandCategory,
+    pub chosen: Action,
+    pub best: Action,
+    /// Expected value given up by the chosen action, in initial bets
+    pub cost: f64,
+    pub values: ActionValues,
+}
+
+impl Grade {
+    /// Whether the chosen action was as good as the best, which it can tie with
+    pub fn correct(&self) -> bool {
+        self.cost <= 1e-9
+    }
+}
+
+/// How many answers of one kind were right
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Accuracy {
+    pub correct: usize,
+    pub total: usize,
+    /// Expected value given up by the wrong decisions, in initial bets
+    pub cost: f64,
+}
+
+impl Accuracy {
+    fn record(&mut self, correct: bool) {
+        self.total += 1;
+        if correct {
+            self.correct += 1;
+        }
+    }
+
+    /// Fraction of answers that were right
+    pub fn rate(&self) -> f64 {
+        self.correct as f64 / self.total.max(1) as f64
+    }
+}
+
+/// Follows the cards a player has seen, grading their decisions and count estimates
+pub struct Trainer {
+    rules: Rules,
+    reference: Reference,
+    counter: Counter,
+    /// Cards not seen since the last shuffle
+    unseen: Composition,
+    decisions: BTreeMap<HandCategory, Accuracy>,
+    running_counts: Accuracy,
+    true_counts: Accuracy,
+}
+
+impl Trainer {
+    pub fn new(rules: Rules, reference: Reference, system: CountingSystem) -> Self {
+        Self {
+            rules,
+            reference,
+            counter: Counter::new(system),
+            unseen: Composition::new(rules.decks),
+            decisions: BTreeMap::new(),
+            running_counts: Accuracy::default(),
+            true_counts: Accuracy::default(),
+        }
+    }
+
+    pub fn card_dealt(&mut self, card: &Card) {
+        self.counter.card_dealt(card);
+        if self.unseen.count(rank(card)) > 0 {
+            self.unseen.remove(rank(card));
+        }
+    }
+
+    pub fn shuffled(&mut self) {
+        self.counter.shuffled();
+        self.unseen = Composition::new(self.rules.decks);
+    }
+
+    pub fn system(&self) -> CountingSystem {
+        self.counter.system()
+    }
+
+    pub fn running_count(&self) -> f32 {
+        self.counter.running_count()
+    }
+
+    pub fn true_count(&self) -> f32 {
+        self.counter.true_count(self.rules.decks)
+    }
+
+    /// Grade choosing `chosen` for `hand`, which along with the upcard has already been seen
+    pub fn grade(
+        &mut self,
+        hand: &Hand,
+        dealer_card: &Card,
+        available: &AvailableActions,
+        chosen: Action,
+    ) -> Grade {
+        let shoe = match self.reference {
+            Reference::Basic => {
+                let mut shoe = Composition::new(self.rules.decks);
+                hand.cards
+                    .iter()
+                    .chain([dealer_card])
+                    .for_each(|card| shoe.remove(rank(card)));
+                shoe
+            }
+            Reference::Count => self.unseen,
+        };
+        let values = Analyzer::new(self.rules, dealer_card).analyze(&shoe, hand);
+
+        let options = [
+            (Action::Stand, Some(values.stand)),
+            (Action::Hit, Some(values.hit)),
+            (Action::Double, values.double.filter(|_| available.double)),
+            (Action::Split, values.split.filter(|_| available.split)),
+            (
+                Action::Surrender,
+                values.surrender.filter(|_| available.surrender),
+            ),
+        ];
+        let (best, best_value) = options
+            .iter()
+            .filter_map(|&(action, value)| Some((action, value?)))
+            .fold((Action::Stand, values.stand), |best, option| {
+                if option.1 > best.1 { option } else { best }
+            });
+        let chosen_value = options
+            .iter()
+            .find(|(action, _)| *action == chosen)
+            .and_then(|(_, value)| *value)
+            .unwrap_or(best_value);
+
+        let grade = Grade {
+            category: HandCategory::of(hand, available),
+            chosen,
+            best,
+            cost: best_value - chosen_value,
+            values,
+        };
+        let accuracy = self.decisions.entry(grade.category).or_default();
+        accuracy.record(grade.correct());
+        accuracy.cost += grade.cost;
+        grade
+    }
+
+    /// Check an estimate of the running count, which must be exact
+    pub fn check_running_count(&mut self, estimate: f32) -> bool {
+        let correct = estimate == self.running_count();
+        self.running_counts.record(correct);
+        correct
+    }
+
+    /// Check an estimate of the true count, within [`TRUE_COUNT_TOLERANCE`]
+    pub fn check_true_count(&mut self, estimate: f32) -> bool {
+        let correct = (estimate - self.true_count()).abs() <= TRUE_COUNT_TOLERANCE;
+        self.true_counts.record(correct);
+        correct
+    }
+
+    /// Accuracy of decisions for each kind of hand played
+    pub fn decisions(&self) -> impl Iterator<Item = (HandCategory, &Accuracy)> {
+        self.decisions
+            .iter()
+            .map(|(&category, accuracy)| (category, accuracy))
+    }
+}
+
+impl Display for Trainer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<16}{:>14}{:>10}", "", "Correct", "EV lost")?;
+        let row = |f: &mut std::fmt::Formatter<'_>, name: &str, accuracy: &Accuracy| {
+            write!(
+                f,
+                "{name:<16}{:>14}",
+                format!(
+                    "{}/{} ({:.0}%)",
+                    accuracy.correct,
+                    accuracy.total,
+                    accuracy.rate() * 100.0
+                )
+            )
+        };
+        for (category, accuracy) in self.decisions() {
+            row(f, category.name(), accuracy)?;
+            writeln!(f, "{:>10.3}", accuracy.cost)?;
+        }
+        row(f, "Running count", &self.running_counts)?;
+        writeln!(f)?;
+        row(f, "True count", &self.true_counts)?;
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cards::card::{Face, Suit};
+
+    use super::*;
+
+    fn card(face: Face) -> Card {
+        Card {
+            face,
+            suit: Suit::Spade,
+        }
+    }
+
+    fn hand(faces: &[Face]) -> Hand {
+        Hand {
+            cards: faces.iter().map(|&face| card(face)).collect(),
+        }
+    }
+
+    #[test]
+    fn grades() {
+        let rules = Rules {
+            decks: 6,
+            ..Rules::default()
+        };
+        let mut trainer = Trainer::new(rules, Reference::Basic, CountingSystem::HI_LO);
+        let available = AvailableActions {
+            double: true,
+            split: false,
+            surrender: false,
+        };
+
+        // Standing on 11 against a 6 gives up most of a double
+        let eleven = hand(&[Face::Six, Face::Five]);
+        let grade = trainer.grade(&eleven, &card(Face::Six), &available, Action::Stand);
+        assert_eq!(grade.best, Action::Double);
+        assert!(!grade.correct());
+        assert!(grade.cost > 0.5, "{}", grade.cost);
+
+        // Doubling isn't offered once the hand has three cards
+        let three_cards = hand(&[Face::Two, Face::Four, Face::Five]);
+        let no_double = AvailableActions {
+            double: false,
+            ..available
+        };
+        let grade = trainer.grade(&three_cards, &card(Face::Six), &no_double, Action::Hit);
+        assert_eq!(grade.best, Action::Hit);
+        assert!(grade.correct());
+
+        let pair = AvailableActions {
+            split: true,
+            ..available
+        };
+        let eights = hand(&[Face::Eight, Face::Eight]);
+        let grade = trainer.grade(&eights, &card(Face::Seven), &pair, Action::Split);
+        assert_eq!(grade.category, HandCategory::Pair);
+        assert!(grade.correct());
+
+        let accuracy: Vec<_> = trainer.decisions().map(|(_, a)| a.correct).collect();
+        assert_eq!(accuracy, [1, 1]);
+
+        // Counting two small cards and a ten
+        for face in [Face::Two, Face::Five, Face::King] {
+            trainer.card_dealt(&card(face));
+        }
+        assert!(!trainer.check_running_count(2.0));
+        assert!(trainer.check_running_count(1.0));
+        assert!(trainer.check_true_count(0.0));
+        trainer.shuffled();
+        assert_eq!(trainer.running_count(), 0.0);
+    }
+}